@@ -0,0 +1,128 @@
+//! Reading the text files under `assets`. Catalogs and themes are made of `key = value` lines,
+//! and each kind of asset is a directory of files named after the language, theme or template
+//! they hold.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// A line that isn't blank, a `#` comment, or `key = value`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InvalidLine {
+    pub line: usize,
+    pub content: String
+}
+
+impl fmt::Display for InvalidLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: expected `key = value`, got `{}`", self.line, self.content)
+    }
+}
+
+/// One `key = value` line, with its line number counted from 1.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Setting<'a> {
+    pub line: usize,
+    pub key: &'a str,
+    pub value: &'a str
+}
+
+/// Splits `text` into settings, skipping blank lines and `#` comments. Keys and values are
+/// trimmed, and only the first `=` separates them, so values may contain `=`.
+pub fn parse_settings(text: &str) -> Result<Vec<Setting>, InvalidLine> {
+    let mut settings = Vec::new();
+    for (idx, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut sides = line.splitn(2, '=');
+        let key = sides.next().unwrap_or("").trim();
+        match sides.next() {
+            Some(value) if !key.is_empty() => settings.push(Setting { line: idx + 1, key: key, value: value.trim() }),
+            _ => return Err(InvalidLine { line: idx + 1, content: line.to_string() })
+        }
+    }
+    Ok(settings)
+}
+
+/// Reads a whole file, returning its name without the extension along with its text.
+pub fn read_named(path: &Path) -> io::Result<(String, String)> {
+    let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("").to_string();
+    let mut text = String::new();
+    try!(try!(File::open(path)).read_to_string(&mut text));
+    Ok((name, text))
+}
+
+/// What was found in an asset directory: the files that loaded, in file name order, and the
+/// ones that were skipped with the reason for each.
+#[derive(Debug)]
+pub struct Loaded<T, E> {
+    pub items: Vec<T>,
+    pub skipped: Vec<(PathBuf, E)>
+}
+
+/// Loads every file in `dir` with the extension `extension`, sorted by file name. A file that
+/// fails to load is skipped rather than failing the whole directory, so one bad translation
+/// or theme doesn't hide the others.
+pub fn load_dir<T, E, F>(dir: &Path, extension: &str, load_file: F) -> io::Result<Loaded<T, E>>
+        where F: Fn(&Path) -> Result<T, E> {
+    let mut paths = try!(fs::read_dir(dir)).filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().map(|ext| ext == extension).unwrap_or(false))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut loaded = Loaded { items: Vec::new(), skipped: Vec::new() };
+    for path in paths.into_iter() {
+        match load_file(&path) {
+            Ok(item) => loaded.items.push(item),
+            Err(err) => loaded.skipped.push((path, err))
+        }
+    }
+    Ok(loaded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::process;
+
+    #[test]
+    fn settings_should_skip_comments_and_split_on_first_equals() {
+        let settings = parse_settings("# comment\n\n  name = Dark  \nformula = a = b").unwrap();
+        assert_eq!(vec![
+            Setting { line: 3, key: "name", value: "Dark" },
+            Setting { line: 4, key: "formula", value: "a = b" }
+        ], settings);
+    }
+
+    #[test]
+    fn lines_without_key_or_equals_should_be_reported() {
+        assert_eq!(Err(InvalidLine { line: 2, content: "name Dark".to_string() }), parse_settings("a = 1\nname Dark"));
+        assert_eq!(Err(InvalidLine { line: 1, content: "= Dark".to_string() }), parse_settings("= Dark"));
+    }
+
+    #[test]
+    fn bad_files_should_be_skipped_and_reported() {
+        let dir = env::temp_dir().join(format!("fabric_roll_assets_test_{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for &(name, text) in [("b.txt", "2"), ("a.txt", "1"), ("c.txt", "x"), ("d.zpl", "4")].iter() {
+            File::create(dir.join(name)).unwrap().write_all(text.as_bytes()).unwrap();
+        }
+
+        let loaded = load_dir(&dir, "txt", |path| {
+            let (_, text) = read_named(path).unwrap();
+            text.parse::<u32>()
+        }).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(vec![1, 2], loaded.items);
+        assert_eq!(1, loaded.skipped.len());
+        assert_eq!(dir.join("c.txt"), loaded.skipped[0].0);
+    }
+}
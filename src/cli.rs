@@ -0,0 +1,245 @@
+use estimator::MaterialRoll;
+use estimator::locale::{NumberLocale, ENGLISH};
+use estimator::quantities::{BasisWeight, Mass, GRAMS_PER_SQUARE_METER};
+use estimator::units::{Length, LengthUnit, Unit, UnitRegistry, INCHES, YARDS};
+use i18n::Translator;
+use report::{self, RollReport, SummaryInputs};
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &'static str = "Usage: fabric-roll [--units FILE] [--unit UNIT] [--output UNIT] [--locale CODE] --thickness T --od OD --id ID
+       [--width W] [--basis-weight GSM] [--report FILE]
+
+Estimates the length of material on a roll. Lengths may include a unit suffix, e.g. `12in` or
+`3.5 pc`; lengths without one are in --unit (default: in). The result is printed in --output
+(default: yrd). Numbers are read and written in --locale (en, de or fr; default: en), so `--locale de`
+accepts `0,08` and prints `1.234,56 yrd`. With no arguments other than --units, the graphical
+interface is started.
+
+With --report, a one page PDF report of the roll is also written to FILE. The roll's weight is
+included when --width and --basis-weight (in g/m²) are given.
+
+Custom units are defined one per line in the --units file, e.g. `Picas (pc) = 0.16667 in`.";
+
+#[derive(Debug, Clone)]
+pub struct CliOptions {
+    pub roll: MaterialRoll,
+    pub output_unit: LengthUnit,
+    pub locale: NumberLocale,
+    pub width: Option<Length>,
+    pub basis_weight: Option<f64>,
+    pub report: Option<PathBuf>
+}
+
+/// Returns the path given with `--units`, if any.
+pub fn units_file(args: &[String]) -> Option<&Path> {
+    args.iter().position(|arg| arg == "--units")
+        .and_then(|idx| args.get(idx + 1))
+        .map(|path| Path::new(path))
+}
+
+/// Whether the arguments ask for a calculation on the command line rather than the GUI.
+pub fn wants_cli(args: &[String]) -> bool {
+    let units_args = if units_file(args).is_some() { 2 } else { 0 };
+    args.len() > units_args
+}
+
+pub fn load_registry(args: &[String]) -> Result<UnitRegistry, String> {
+    let mut registry = UnitRegistry::new();
+    if let Some(path) = units_file(args) {
+        try!(registry.load_file(path).map_err(|err| format!("{}: {}", path.display(), err)));
+    }
+    Ok(registry)
+}
+
+pub fn parse_args(args: &[String], registry: &UnitRegistry) -> Result<CliOptions, String> {
+    let mut input_unit: LengthUnit = INCHES;
+    let mut output_unit: LengthUnit = YARDS;
+    let mut locale: NumberLocale = ENGLISH;
+    let mut thickness: Option<&str> = None;
+    let mut od: Option<&str> = None;
+    let mut id: Option<&str> = None;
+    let mut width: Option<&str> = None;
+    let mut basis_weight: Option<&str> = None;
+    let mut report: Option<PathBuf> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value: &str = try!(iter.next().ok_or_else(|| format!("Missing value for {}", arg)));
+        match arg.as_str() {
+            "--units" => {},
+            "--unit" => input_unit = try!(find_unit(registry, value)),
+            "--output" => output_unit = try!(find_unit(registry, value)),
+            "--locale" => locale = try!(NumberLocale::from_code(value).ok_or_else(|| format!("Unknown locale: {}", value))),
+            "--thickness" => thickness = Some(value),
+            "--od" => od = Some(value),
+            "--id" => id = Some(value),
+            "--width" => width = Some(value),
+            "--basis-weight" => basis_weight = Some(value),
+            "--report" => report = Some(PathBuf::from(value)),
+            _ => return Err(format!("Unknown argument: {}", arg))
+        }
+    }
+
+    let thickness = try!(parse_length(registry, "--thickness", thickness, &input_unit, &locale));
+    let od = try!(parse_length(registry, "--od", od, &input_unit, &locale));
+    let id = try!(parse_length(registry, "--id", id, &input_unit, &locale));
+
+    if thickness <= Length::zero() || id <= Length::zero() || od <= id {
+        return Err("Thickness and ID must be greater than zero, and OD must be greater than ID".to_string());
+    }
+
+    let width = match width {
+        Some(_) => Some(try!(parse_length(registry, "--width", width, &input_unit, &locale))),
+        None => None
+    };
+    let basis_weight = match basis_weight {
+        Some(value) => match locale.parse_number(value) {
+            Some(grams_per_sq_m) if grams_per_sq_m > 0.0 => Some(grams_per_sq_m),
+            _ => return Err(format!("Invalid basis weight: {}", value))
+        },
+        None => None
+    };
+
+    Ok(CliOptions {
+        roll: MaterialRoll { id: id, od: od, thickness: thickness },
+        output_unit: output_unit,
+        locale: locale,
+        width: width,
+        basis_weight: basis_weight,
+        report: report
+    })
+}
+
+/// Weight of the whole roll, when its width and basis weight are known.
+pub fn roll_weight(options: &CliOptions) -> Option<Mass> {
+    match (options.width.as_ref(), options.basis_weight) {
+        (Some(width), Some(grams_per_sq_m)) =>
+            Some(options.roll.get_weight(width.clone(), BasisWeight::new(grams_per_sq_m, GRAMS_PER_SQUARE_METER))),
+        _ => None
+    }
+}
+
+/// The report for the calculated roll, with the chart's diameters in the unit the OD was given
+/// in and lengths in the output unit.
+pub fn build_report(options: &CliOptions) -> RollReport {
+    let locale = &options.locale;
+    let roll = &options.roll;
+    let translator = Translator::new();
+    let inputs = SummaryInputs {
+        thickness: locale.format_length(&roll.thickness),
+        od: locale.format_length(&roll.od),
+        id: locale.format_length(&roll.id),
+        width: options.width.as_ref().map(|width| locale.format_length(width)),
+        basis_weight: options.basis_weight.map(|grams_per_sq_m| locale.format_number(grams_per_sq_m, 1))
+    };
+
+    let length = locale.format_length(&roll.get_roll_length().convert_to(options.output_unit.clone()));
+    let lines = report::summary_lines(roll, &inputs, &length, roll_weight(options), locale, &translator);
+    RollReport::new(roll.clone(), lines, roll.od.unit.clone(), options.output_unit.clone(), options.locale, &translator)
+}
+
+/// Runs a calculation from the command line, returning the process exit code.
+pub fn run(args: &[String], registry: &UnitRegistry) -> i32 {
+    match parse_args(args, registry) {
+        Ok(options) => {
+            println!("{}", options.locale.format_length(&options.roll.get_roll_length().convert_to(options.output_unit.clone())));
+            match options.report {
+                Some(ref path) => match build_report(&options).save(path) {
+                    Ok(()) => 0,
+                    Err(err) => {
+                        let _ = writeln!(io::stderr(), "Unable to write report {}: {}", path.display(), err);
+                        1
+                    }
+                },
+                None => 0
+            }
+        }
+        Err(message) => {
+            let _ = writeln!(io::stderr(), "{}\n\n{}", message, USAGE);
+            1
+        }
+    }
+}
+
+fn find_unit(registry: &UnitRegistry, name: &str) -> Result<LengthUnit, String> {
+    registry.find(name).cloned().ok_or_else(|| format!("Unknown unit: {}", name))
+}
+
+fn parse_length(registry: &UnitRegistry, name: &str, value: Option<&str>, unit: &LengthUnit,
+        locale: &NumberLocale) -> Result<Length, String> {
+    value.ok_or_else(|| format!("Missing required argument {}", name))
+        .and_then(|value| registry.parse_localized(value, unit, locale).ok_or_else(|| format!("Invalid length for {}: {}", name, value)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use estimator::units::{Unit, UnitRegistry, Length, CENTIMETERS, INCHES, METERS, YARDS};
+    use estimator::quantities::KILOGRAMS;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_args_should_use_default_units() {
+        let options = parse_args(&args("--thickness 0.08 --od 12 --id 4"), &UnitRegistry::new()).unwrap();
+        assert_eq!(Length::new(12.0, INCHES), options.roll.od);
+        assert_eq!(YARDS, options.output_unit);
+    }
+
+    #[test]
+    fn parse_args_should_accept_unit_suffixes_and_custom_units() {
+        let mut registry = UnitRegistry::new();
+        registry.load_str("Ells (ell) = 45 in").unwrap();
+
+        let options = parse_args(&args("--unit cm --output ell --thickness 0.2 --od 1ell --id 10"), &registry).unwrap();
+        assert_eq!(Length::new(10.0, CENTIMETERS), options.roll.id);
+        assert_eq!("ell", options.roll.od.unit.abbrev());
+        assert_eq!("Ells", options.output_unit.full_name());
+    }
+
+    #[test]
+    fn parse_args_should_read_numbers_in_the_given_locale() {
+        let options = parse_args(&args("--locale de --unit cm --thickness 0,2 --od 1.030,5 --id 10"), &UnitRegistry::new()).unwrap();
+        assert_eq!(Length::new(0.2, CENTIMETERS), options.roll.thickness);
+        assert_eq!(Length::new(1030.5, CENTIMETERS), options.roll.od);
+        assert!(parse_args(&args("--locale xx"), &UnitRegistry::new()).unwrap_err().contains("xx"));
+    }
+
+    #[test]
+    fn parse_args_should_report_missing_and_unknown_arguments() {
+        let registry = UnitRegistry::new();
+        assert!(parse_args(&args("--thickness 0.08 --od 12"), &registry).unwrap_err().contains("--id"));
+        assert!(parse_args(&args("--color red"), &registry).unwrap_err().contains("--color"));
+        assert!(parse_args(&args("--unit cubits"), &registry).unwrap_err().contains("cubits"));
+    }
+
+    #[test]
+    fn report_should_include_weight_when_width_and_basis_weight_are_given() {
+        let registry = UnitRegistry::new();
+        let options = parse_args(&args("--unit cm --output m --thickness 0.08 --od 12 --id 4 --report roll.pdf"), &registry).unwrap();
+        assert_eq!(Some(PathBuf::from("roll.pdf")), options.report);
+        let report = build_report(&options);
+        assert_eq!(vec!["Material Thickness: 0.08 cm", "Outside Diameter: 12.00 cm", "Inside Diameter: 4.00 cm"],
+            report.lines[..3].to_vec());
+        assert_eq!("Layers: 100", report.lines[4]);
+        assert_eq!(5, report.lines.len());
+        assert_eq!("Diameter (cm)", report.diameter_label);
+
+        let options = parse_args(&args("--unit cm --thickness 0.08 --od 12 --id 4 --width 1m --basis-weight 80"), &registry).unwrap();
+        let weight = roll_weight(&options).unwrap().value(&KILOGRAMS);
+        assert!((weight - options.roll.get_roll_length().value(&METERS) * 0.08).abs() < 0.0001);
+        assert_eq!(format!("Weight: {:.2} kg", weight), build_report(&options).lines[7]);
+        assert!(parse_args(&args("--thickness 0.08 --od 12 --id 4 --basis-weight heavy"), &registry).unwrap_err().contains("heavy"));
+    }
+
+    #[test]
+    fn units_file_alone_should_start_the_gui() {
+        assert!(!wants_cli(&args("--units units.txt")));
+        assert!(!wants_cli(&args("")));
+        assert!(wants_cli(&args("--units units.txt --od 12")));
+    }
+}
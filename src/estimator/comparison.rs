@@ -0,0 +1,232 @@
+use std::cmp::Ordering;
+
+use super::MaterialRoll;
+use super::cost::{CostEstimate, Price};
+use super::quantities::BasisWeight;
+use super::units::{Length, LengthUnit, METERS};
+
+/// A supplier's roll format to be compared against others.
+#[derive(Debug, Clone)]
+pub struct RollFormat {
+    pub name: String,
+    pub roll: MaterialRoll,
+    pub width: Length,
+    pub basis_weight: Option<BasisWeight>,
+    pub price: Option<Price>
+}
+
+/// Computed metrics for a single `RollFormat`, along with its dimensions so rows can be sorted
+/// by any column. Lengths are expressed in the unit passed to `compare`, and areas in square
+/// meters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RollMetrics {
+    pub name: String,
+    pub thickness: Length,
+    pub od: Length,
+    pub id: Length,
+    pub width: Length,
+    pub length: Length,
+    pub area: f64,
+    pub price_per_roll: Option<f64>,
+    pub price_per_length: Option<f64>,
+    pub price_per_area: Option<f64>
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SortColumn {
+    Name,
+    Thickness,
+    Od,
+    Id,
+    Width,
+    Length,
+    Area,
+    PricePerRoll,
+    PricePerLength,
+    PricePerArea
+}
+
+pub const SORT_COLUMNS: [SortColumn; 10] = [
+    SortColumn::Name,
+    SortColumn::Thickness,
+    SortColumn::Od,
+    SortColumn::Id,
+    SortColumn::Width,
+    SortColumn::Length,
+    SortColumn::Area,
+    SortColumn::PricePerRoll,
+    SortColumn::PricePerLength,
+    SortColumn::PricePerArea
+];
+
+impl RollMetrics {
+
+    pub fn new(format: &RollFormat, unit: LengthUnit) -> RollMetrics {
+        let length = format.roll.get_roll_length().convert_to(unit.clone());
+        let area = length.value(&METERS) * format.width.value(&METERS);
+        let cost = format.price.and_then(|price| {
+            CostEstimate::new(&format.roll, &format.width, format.basis_weight.clone(), price, unit.clone())
+        });
+
+        RollMetrics {
+            name: format.name.clone(),
+            thickness: format.roll.thickness.clone(),
+            od: format.roll.od.clone(),
+            id: format.roll.id.clone(),
+            width: format.width.clone(),
+            length: length,
+            area: area,
+            price_per_roll: cost.as_ref().map(|cost| cost.roll_cost),
+            price_per_length: cost.as_ref().map(|cost| cost.cost_per_length),
+            price_per_area: cost.as_ref().and_then(|cost| divide(cost.roll_cost, area))
+        }
+    }
+
+    fn compare_by(&self, other: &RollMetrics, column: SortColumn) -> Ordering {
+        match column {
+            SortColumn::Name => self.name.cmp(&other.name),
+            SortColumn::Thickness => compare_lengths(&self.thickness, &other.thickness),
+            SortColumn::Od => compare_lengths(&self.od, &other.od),
+            SortColumn::Id => compare_lengths(&self.id, &other.id),
+            SortColumn::Width => compare_lengths(&self.width, &other.width),
+            SortColumn::Length => compare_lengths(&self.length, &other.length),
+            SortColumn::Area => compare_f64(Some(self.area), Some(other.area)),
+            SortColumn::PricePerRoll => compare_f64(self.price_per_roll, other.price_per_roll),
+            SortColumn::PricePerLength => compare_f64(self.price_per_length, other.price_per_length),
+            SortColumn::PricePerArea => compare_f64(self.price_per_area, other.price_per_area)
+        }
+    }
+}
+
+pub fn compare(formats: &[RollFormat], unit: LengthUnit) -> Vec<RollMetrics> {
+    formats.iter().map(|format| RollMetrics::new(format, unit.clone())).collect()
+}
+
+/// Sorts rows by the given column. Rows without a price always sort after rows that have one,
+/// regardless of direction.
+pub fn sort_metrics(rows: &mut Vec<RollMetrics>, column: SortColumn, ascending: bool) {
+    rows.sort_by(|a, b| compare_rows(a, b, column, ascending));
+}
+
+/// Returns the indices of `rows` in the order `sort_metrics` would put them, for callers that
+/// need to keep rows alongside their original inputs.
+pub fn sorted_indices(rows: &[RollMetrics], column: SortColumn, ascending: bool) -> Vec<usize> {
+    let mut indices = (0..rows.len()).collect::<Vec<usize>>();
+    indices.sort_by(|a, b| compare_rows(&rows[*a], &rows[*b], column, ascending));
+    indices
+}
+
+fn compare_rows(a: &RollMetrics, b: &RollMetrics, column: SortColumn, ascending: bool) -> Ordering {
+    let ordering = a.compare_by(b, column);
+    if is_missing(a, column) || is_missing(b, column) || ascending {
+        ordering
+    } else {
+        ordering.reverse()
+    }
+}
+
+fn is_missing(row: &RollMetrics, column: SortColumn) -> bool {
+    match column {
+        SortColumn::PricePerRoll => row.price_per_roll.is_none(),
+        SortColumn::PricePerLength => row.price_per_length.is_none(),
+        SortColumn::PricePerArea => row.price_per_area.is_none(),
+        _ => false
+    }
+}
+
+fn compare_lengths(a: &Length, b: &Length) -> Ordering {
+    compare_f64(Some(a.value), Some(b.value(&a.unit)))
+}
+
+fn compare_f64(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal
+    }
+}
+
+fn divide(numerator: f64, denominator: f64) -> Option<f64> {
+    if denominator > 0.0 {
+        Some(numerator / denominator)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::MaterialRoll;
+    use ::estimator::cost::Price;
+    use ::estimator::units::{Length, CENTIMETERS, METERS};
+
+    fn format(name: &str, od: f64, price: Option<f64>) -> RollFormat {
+        RollFormat {
+            name: name.to_string(),
+            roll: MaterialRoll {
+                id: Length::new(10.0, CENTIMETERS),
+                od: Length::new(od, CENTIMETERS),
+                thickness: Length::new(1.0, CENTIMETERS)
+            },
+            width: Length::new(2.0, METERS),
+            basis_weight: None,
+            price: price.map(Price::PerRoll)
+        }
+    }
+
+    #[test]
+    fn metrics_should_include_area_and_unit_prices() {
+        let metrics = RollMetrics::new(&format("a", 30.0, Some(100.0)), METERS);
+        let length = metrics.length.value;
+        assert!((metrics.area - length * 2.0).abs() < 0.00001);
+        assert!((metrics.price_per_length.unwrap() - 100.0 / length).abs() < 0.00001);
+        assert!((metrics.price_per_area.unwrap() - 100.0 / (length * 2.0)).abs() < 0.00001);
+    }
+
+    #[test]
+    fn sorting_by_length_descending_should_put_longest_roll_first() {
+        let formats = vec![format("small", 20.0, None), format("large", 40.0, None), format("medium", 30.0, None)];
+        let mut rows = compare(&formats, METERS);
+        sort_metrics(&mut rows, SortColumn::Length, false);
+        let names = rows.iter().map(|row| row.name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(vec!["large", "medium", "small"], names);
+    }
+
+    #[test]
+    fn rows_without_price_should_sort_last_in_either_direction() {
+        let formats = vec![format("none", 20.0, None), format("cheap", 40.0, Some(10.0)), format("dear", 30.0, Some(90.0))];
+        let mut rows = compare(&formats, METERS);
+
+        sort_metrics(&mut rows, SortColumn::PricePerRoll, true);
+        assert_eq!("none", rows[2].name);
+
+        sort_metrics(&mut rows, SortColumn::PricePerRoll, false);
+        assert_eq!("dear", rows[0].name);
+        assert_eq!("none", rows[2].name);
+    }
+
+    #[test]
+    fn rows_should_sort_by_input_columns() {
+        let mut thin = format("thin", 30.0, None);
+        thin.roll.thickness = Length::new(0.5, CENTIMETERS);
+        thin.width = Length::new(150.0, CENTIMETERS);
+        let formats = vec![format("large", 40.0, None), thin, format("small", 20.0, None)];
+        let rows = compare(&formats, METERS);
+
+        assert_eq!(vec![1, 0, 2], sorted_indices(&rows, SortColumn::Thickness, true));
+        assert_eq!(vec![2, 1, 0], sorted_indices(&rows, SortColumn::Od, true));
+        assert_eq!(vec![0, 1, 2], sorted_indices(&rows, SortColumn::Od, false));
+        assert_eq!(vec![1, 0, 2], sorted_indices(&rows, SortColumn::Width, true));
+        assert_eq!(vec![0, 1, 2], sorted_indices(&rows, SortColumn::Id, true));
+    }
+
+    #[test]
+    fn sorted_indices_should_not_reorder_rows() {
+        let formats = vec![format("b", 20.0, None), format("a", 40.0, None)];
+        let rows = compare(&formats, METERS);
+        assert_eq!(vec![1, 0], sorted_indices(&rows, SortColumn::Name, true));
+        assert_eq!("b", rows[0].name);
+    }
+}
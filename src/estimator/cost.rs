@@ -0,0 +1,131 @@
+use super::MaterialRoll;
+use super::locale::NumberLocale;
+use super::quantities::{Area, BasisWeight, KILOGRAMS, SQUARE_METERS};
+use super::units::{Length, LengthUnit, Unit};
+
+/// How the price of a roll is quoted by the supplier.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Price {
+    PerRoll(f64),
+    PerKilogram(f64),
+    PerSquareMeter(f64)
+}
+
+pub const PRICE_BASIS_NAMES: [&'static str; 3] = ["Per Roll", "Per kg", "Per m²"];
+
+impl Price {
+
+    /// Creates a price from an index into `PRICE_BASIS_NAMES`.
+    pub fn from_basis(basis: usize, value: f64) -> Option<Price> {
+        match basis {
+            0 => Some(Price::PerRoll(value)),
+            1 => Some(Price::PerKilogram(value)),
+            2 => Some(Price::PerSquareMeter(value)),
+            _ => None
+        }
+    }
+}
+
+/// Cost of a single roll, broken down per unit length and per unit area of `unit`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CostEstimate {
+    pub roll_cost: f64,
+    pub cost_per_length: f64,
+    pub cost_per_area: f64,
+    pub unit: LengthUnit
+}
+
+impl CostEstimate {
+
+    /// Estimates the cost of `roll`. `basis_weight` is only required when the price is quoted per
+    /// kilogram. Returns `None` if the roll has no length
+    /// or area, or if a per kilogram price is given without a basis weight.
+    pub fn new(roll: &MaterialRoll, width: &Length, basis_weight: Option<BasisWeight>, price: Price, unit: LengthUnit) -> Option<CostEstimate> {
+        let length = roll.get_roll_length();
+        let area: Area = length.clone() * width.clone();
+        let area_sq_m = area.value(&SQUARE_METERS);
+        if area_sq_m <= 0.0 {
+            return None;
+        }
+
+        let roll_cost = match price {
+            Price::PerRoll(value) => Some(value),
+            Price::PerSquareMeter(value) => Some(value * area_sq_m),
+            Price::PerKilogram(value) => basis_weight.map(|basis_weight| {
+                value * (area * basis_weight).value(&KILOGRAMS)
+            })
+        };
+
+        roll_cost.map(|roll_cost| {
+            let length_val = length.value(&unit);
+            let area_val = length_val * width.value(&unit);
+            CostEstimate {
+                roll_cost: roll_cost,
+                cost_per_length: roll_cost / length_val,
+                cost_per_area: roll_cost / area_val,
+                unit: unit
+            }
+        })
+    }
+
+    pub fn format_per_length(&self, locale: &NumberLocale) -> String {
+        format!("{} / {}", locale.format_number(self.cost_per_length, 4), self.unit.abbrev())
+    }
+
+    pub fn format_per_area(&self, locale: &NumberLocale) -> String {
+        format!("{} / {}²", locale.format_number(self.cost_per_area, 4), self.unit.abbrev())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::MaterialRoll;
+    use ::estimator::locale::{ENGLISH, GERMAN};
+    use ::estimator::quantities::GRAMS_PER_SQUARE_METER;
+    use ::estimator::units::{Length, CENTIMETERS, METERS, YARDS};
+
+    const EPSILON: f64 = 0.00001;
+
+    fn roll() -> MaterialRoll {
+        MaterialRoll {
+            id: Length::new(10.0, CENTIMETERS),
+            od: Length::new(30.0, CENTIMETERS),
+            thickness: Length::new(0.1, CENTIMETERS)
+        }
+    }
+
+    #[test]
+    fn price_per_roll_should_be_divided_by_length_in_output_unit() {
+        let width = Length::new(1.0, METERS);
+        let length = roll().get_roll_length();
+        let estimate = CostEstimate::new(&roll(), &width, None, Price::PerRoll(100.0), YARDS).unwrap();
+        assert!((estimate.roll_cost - 100.0).abs() < EPSILON);
+        assert!((estimate.cost_per_length - 100.0 / length.value(&YARDS)).abs() < EPSILON);
+        assert_eq!("yrd", estimate.format_per_length(&ENGLISH).split(" / ").last().unwrap());
+        assert_eq!(format!("{} / yrd", GERMAN.format_number(estimate.cost_per_length, 4)), estimate.format_per_length(&GERMAN));
+    }
+
+    #[test]
+    fn price_per_square_meter_should_be_multiplied_by_area() {
+        let width = Length::new(2.0, METERS);
+        let length = roll().get_roll_length().value(&METERS);
+        let estimate = CostEstimate::new(&roll(), &width, None, Price::PerSquareMeter(3.0), METERS).unwrap();
+        assert!((estimate.roll_cost - 3.0 * 2.0 * length).abs() < EPSILON);
+        assert!((estimate.cost_per_area - 3.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn price_per_kilogram_should_use_basis_weight() {
+        let width = Length::new(1.0, METERS);
+        let length = roll().get_roll_length().value(&METERS);
+        let estimate = CostEstimate::new(&roll(), &width, Some(BasisWeight::new(200.0, GRAMS_PER_SQUARE_METER)), Price::PerKilogram(5.0), METERS).unwrap();
+        assert!((estimate.roll_cost - 5.0 * 0.2 * length).abs() < EPSILON);
+    }
+
+    #[test]
+    fn price_per_kilogram_without_basis_weight_should_return_none() {
+        let width = Length::new(1.0, METERS);
+        assert!(CostEstimate::new(&roll(), &width, None, Price::PerKilogram(5.0), METERS).is_none());
+    }
+}
@@ -0,0 +1,348 @@
+use super::MaterialRoll;
+use super::units::{Length, LengthUnit, CENTIMETERS};
+
+/// Maximum number of search nodes visited by the exact optimizer before it gives up and falls
+/// back to the greedy assignment.
+pub const EXACT_SEARCH_NODE_LIMIT: usize = 250_000;
+
+/// Largest order, counted in pieces, and largest inventory the exact optimizer is tried on. The
+/// search recurses once per piece, so bigger problems go straight to the greedy assignment.
+pub const EXACT_SEARCH_PIECE_LIMIT: u64 = 64;
+pub const EXACT_SEARCH_ROLL_LIMIT: usize = 32;
+
+/// A partially used roll on the shelf. The length available is whatever remains between the core
+/// and `current_od`.
+#[derive(Debug, Clone)]
+pub struct InventoryRoll {
+    pub label: String,
+    pub roll: MaterialRoll,
+    pub current_od: Length
+}
+
+impl InventoryRoll {
+
+    pub fn get_remaining_length(&self) -> Length {
+        self.roll.get_length_at_diameter(&self.current_od)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PieceOrder {
+    pub length: Length,
+    pub quantity: u32
+}
+
+/// The pieces cut from a single inventory roll, with equal pieces counted together. Lengths are
+/// in centimeters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CutAssignment {
+    pub roll_index: usize,
+    pub pieces: Vec<PieceOrder>,
+    pub used: Length,
+    pub leftover: Length
+}
+
+/// The result of `optimize_cuts`. `waste` is the total leftover on every roll that was cut from,
+/// and `exact` is false when the greedy fallback produced the plan.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CutPlan {
+    pub assignments: Vec<CutAssignment>,
+    pub unassigned: Vec<PieceOrder>,
+    pub waste: Length,
+    pub exact: bool
+}
+
+/// Assigns every ordered piece to a roll from the inventory. The plan first places as much of
+/// the order as possible, then minimizes the leftover on the rolls it cuts from, so untouched
+/// rolls stay whole on the shelf.
+pub fn optimize_cuts(inventory: &[InventoryRoll], orders: &[PieceOrder]) -> CutPlan {
+    let unit: LengthUnit = CENTIMETERS;
+    let capacities = inventory.iter()
+        .map(|item| item.get_remaining_length().value(&unit))
+        .collect::<Vec<f64>>();
+
+    let mut groups = orders.iter()
+        .map(|order| (order.length.value(&unit), order.quantity))
+        .filter(|&(piece, quantity)| piece > 0.0 && quantity > 0)
+        .collect::<Vec<(f64, u32)>>();
+    groups.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+
+    let piece_count: u64 = groups.iter().map(|&(_, quantity)| quantity as u64).sum();
+    if piece_count <= EXACT_SEARCH_PIECE_LIMIT && capacities.len() <= EXACT_SEARCH_ROLL_LIMIT {
+        let pieces = groups.iter()
+            .flat_map(|&(piece, quantity)| (0..quantity).map(move |_| piece))
+            .collect::<Vec<f64>>();
+        if let Some(assignment) = exact_assignment(&capacities, &pieces) {
+            let cuts = pieces.iter().zip(assignment.into_iter())
+                .map(|(piece, roll_opt)| (*piece, roll_opt, 1))
+                .collect::<Vec<Cut>>();
+            return build_plan(&capacities, &cuts, true);
+        }
+    }
+
+    let cuts = greedy_assignment(&capacities, &groups);
+    build_plan(&capacities, &cuts, false)
+}
+
+/// A number of equal pieces and the roll they are cut from, if any.
+type Cut = (f64, Option<usize>, u32);
+
+/// Best-fit decreasing: each piece, longest first, goes to the already opened roll it fits most
+/// tightly, or else opens the smallest unopened roll that can hold it.
+fn greedy_assignment(capacities: &[f64], groups: &[(f64, u32)]) -> Vec<Cut> {
+    let mut remaining = capacities.to_vec();
+    let mut opened = vec![false; capacities.len()];
+    let mut cuts = Vec::new();
+
+    for &(piece, quantity) in groups.iter() {
+        let mut left = quantity;
+        while left > 0 {
+            let best_open = tightest_fit(&remaining, piece, |idx| opened[idx]);
+            match best_open.or_else(|| tightest_fit(&remaining, piece, |idx| !opened[idx])) {
+                Some(idx) => {
+                    // The tightest roll stays the tightest as pieces go on it, so it takes as
+                    // many of them as fit.
+                    let count = (remaining[idx] / piece).floor().max(1.0).min(left as f64) as u32;
+                    remaining[idx] -= piece * count as f64;
+                    opened[idx] = true;
+                    cuts.push((piece, Some(idx), count));
+                    left -= count;
+                }
+                None => {
+                    cuts.push((piece, None, left));
+                    left = 0;
+                }
+            }
+        }
+    }
+    cuts
+}
+
+fn tightest_fit<F>(remaining: &[f64], piece: f64, filter: F) -> Option<usize> where F: Fn(usize) -> bool {
+    remaining.iter().enumerate()
+        .filter(|&(idx, capacity)| filter(idx) && *capacity >= piece)
+        .min_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+        .map(|(idx, _)| idx)
+}
+
+struct Search<'a> {
+    capacities: &'a [f64],
+    pieces: &'a [f64],
+    remaining: Vec<f64>,
+    opened: Vec<bool>,
+    current: Vec<Option<usize>>,
+    best: Option<(f64, f64, Vec<Option<usize>>)>,
+    nodes: usize
+}
+
+impl<'a> Search<'a> {
+
+    /// Returns false once the node budget is exhausted.
+    fn visit(&mut self, piece_idx: usize, unassigned: f64) -> bool {
+        self.nodes += 1;
+        if self.nodes > EXACT_SEARCH_NODE_LIMIT {
+            return false;
+        }
+
+        let waste_bound = self.waste_lower_bound(piece_idx);
+        if let Some((best_unassigned, best_waste, _)) = self.best {
+            if unassigned > best_unassigned || (unassigned == best_unassigned && waste_bound >= best_waste) {
+                return true;
+            }
+        }
+
+        if piece_idx == self.pieces.len() {
+            self.best = Some((unassigned, waste_bound, self.current.clone()));
+            return true;
+        }
+
+        let piece = self.pieces[piece_idx];
+        let mut tried: Vec<(bool, f64)> = Vec::new();
+        for roll_idx in 0..self.capacities.len() {
+            let key = (self.opened[roll_idx], self.remaining[roll_idx]);
+            if self.remaining[roll_idx] < piece || tried.contains(&key) {
+                continue;
+            }
+            tried.push(key);
+
+            let was_opened = self.opened[roll_idx];
+            self.remaining[roll_idx] -= piece;
+            self.opened[roll_idx] = true;
+            self.current.push(Some(roll_idx));
+
+            let completed = self.visit(piece_idx + 1, unassigned);
+
+            self.current.pop();
+            self.opened[roll_idx] = was_opened;
+            self.remaining[roll_idx] += piece;
+            if !completed {
+                return false;
+            }
+        }
+
+        self.current.push(None);
+        let completed = self.visit(piece_idx + 1, unassigned + piece);
+        self.current.pop();
+        completed
+    }
+
+    /// Leftover on opened rolls can only shrink by as much as the pieces still to be placed.
+    fn waste_lower_bound(&self, piece_idx: usize) -> f64 {
+        let open_capacity: f64 = self.remaining.iter().zip(self.opened.iter())
+            .filter(|&(_, opened)| *opened)
+            .map(|(capacity, _)| *capacity)
+            .sum();
+        let pieces_left: f64 = self.pieces[piece_idx..].iter().sum();
+        (open_capacity - pieces_left).max(0.0)
+    }
+}
+
+fn exact_assignment(capacities: &[f64], pieces: &[f64]) -> Option<Vec<Option<usize>>> {
+    let mut search = Search {
+        capacities: capacities,
+        pieces: pieces,
+        remaining: capacities.to_vec(),
+        opened: vec![false; capacities.len()],
+        current: Vec::with_capacity(pieces.len()),
+        best: None,
+        nodes: 0
+    };
+
+    if search.visit(0, 0.0) {
+        search.best.map(|(_, _, assignment)| assignment)
+    } else {
+        None
+    }
+}
+
+fn build_plan(capacities: &[f64], cuts: &[Cut], exact: bool) -> CutPlan {
+    let unit: LengthUnit = CENTIMETERS;
+    let mut assignments: Vec<CutAssignment> = Vec::new();
+    let mut unassigned: Vec<PieceOrder> = Vec::new();
+
+    for &(piece, roll_opt, count) in cuts.iter() {
+        match roll_opt {
+            Some(roll_idx) => {
+                let position = assignments.iter().position(|cut| cut.roll_index == roll_idx);
+                let idx = position.unwrap_or_else(|| {
+                    assignments.push(CutAssignment {
+                        roll_index: roll_idx,
+                        pieces: Vec::new(),
+                        used: Length::new(0.0, unit.clone()),
+                        leftover: Length::new(capacities[roll_idx], unit.clone())
+                    });
+                    assignments.len() - 1
+                });
+                let cut = &mut assignments[idx];
+                add_pieces(&mut cut.pieces, piece, count);
+                cut.used = Length::new(cut.used.value + piece * count as f64, unit.clone());
+                cut.leftover = Length::new(cut.leftover.value - piece * count as f64, unit.clone());
+            }
+            None => add_pieces(&mut unassigned, piece, count)
+        }
+    }
+
+    assignments.sort_by_key(|cut| cut.roll_index);
+    let waste = assignments.iter().map(|cut| cut.leftover.value).sum();
+
+    CutPlan {
+        assignments: assignments,
+        unassigned: unassigned,
+        waste: Length::new(waste, unit),
+        exact: exact
+    }
+}
+
+/// Pieces arrive longest first, so equal pieces are always next to each other.
+fn add_pieces(pieces: &mut Vec<PieceOrder>, piece: f64, count: u32) {
+    if let Some(last) = pieces.last_mut() {
+        if last.length.value == piece {
+            last.quantity += count;
+            return;
+        }
+    }
+    pieces.push(PieceOrder { length: Length::new(piece, CENTIMETERS), quantity: count });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::test::roll_holding;
+    use ::estimator::units::{Length, CENTIMETERS};
+
+    const EPSILON: f64 = 0.0001;
+
+    // A single layer roll holding exactly `length` cm.
+    fn inventory_roll(length: f64) -> InventoryRoll {
+        let roll = roll_holding(length, 1, 1.0);
+        InventoryRoll {
+            label: format!("{} cm", length),
+            current_od: roll.od.clone(),
+            roll: roll
+        }
+    }
+
+    fn order(length: f64, quantity: u32) -> PieceOrder {
+        PieceOrder { length: Length::new(length, CENTIMETERS), quantity: quantity }
+    }
+
+    #[test]
+    fn remaining_length_should_only_count_material_below_current_od() {
+        let mut item = inventory_roll(100.0);
+        item.current_od = item.roll.id.clone();
+        assert!(item.get_remaining_length().value < EPSILON);
+    }
+
+    #[test]
+    fn optimizer_should_pick_roll_combination_with_least_waste() {
+        // Best fit decreasing would put the 60 on the 70 roll; the exact search finds that the
+        // 100 roll takes both pieces with nothing left over.
+        let inventory = vec![inventory_roll(70.0), inventory_roll(100.0)];
+        let plan = optimize_cuts(&inventory, &[order(60.0, 1), order(40.0, 1)]);
+
+        assert!(plan.exact);
+        assert!(plan.unassigned.is_empty());
+        assert_eq!(1, plan.assignments.len());
+        assert_eq!(1, plan.assignments[0].roll_index);
+        assert!(plan.waste.value < EPSILON);
+    }
+
+    #[test]
+    fn pieces_that_do_not_fit_should_be_reported_as_unassigned() {
+        let inventory = vec![inventory_roll(50.0)];
+        let plan = optimize_cuts(&inventory, &[order(30.0, 2)]);
+        assert_eq!(vec![order(30.0, 1)], plan.unassigned);
+        assert!((plan.waste.value - 20.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn greedy_assignment_should_use_best_fit_decreasing() {
+        let assignment = greedy_assignment(&[70.0, 100.0], &[(60.0, 1), (40.0, 1)]);
+        assert_eq!(vec![(60.0, Some(0), 1), (40.0, Some(1), 1)], assignment);
+    }
+
+    #[test]
+    fn large_problems_should_fall_back_to_greedy() {
+        let inventory = (0..12).map(|i| inventory_roll(100.0 + i as f64)).collect::<Vec<InventoryRoll>>();
+        let plan = optimize_cuts(&inventory, &[order(7.0, 60), order(11.0, 40)]);
+        assert!(!plan.exact);
+        assert!(plan.unassigned.is_empty());
+    }
+
+    #[test]
+    fn huge_orders_should_be_cut_without_listing_every_piece() {
+        let inventory = vec![inventory_roll(100.0), inventory_roll(200.0)];
+        let plan = optimize_cuts(&inventory, &[order(0.001, ::std::u32::MAX)]);
+
+        let placed: u64 = plan.assignments.iter()
+            .flat_map(|cut| cut.pieces.iter())
+            .map(|pieces| pieces.quantity as u64)
+            .sum();
+        let unassigned: u64 = plan.unassigned.iter().map(|pieces| pieces.quantity as u64).sum();
+        assert!(!plan.exact);
+        assert_eq!(2, plan.assignments.len());
+        assert_eq!(1, plan.unassigned.len());
+        assert_eq!(::std::u32::MAX as u64, placed + unassigned);
+        assert!(placed > 299_000);
+    }
+}
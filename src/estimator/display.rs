@@ -0,0 +1,138 @@
+//! Formatting of lengths for display. Rather than always printing a result in the unit it was
+//! calculated in, a `DisplayFormat` picks the most readable unit from a unit system, so a short
+//! offcut shows as millimeters and a long run as kilometers.
+
+use super::locale::NumberLocale;
+use super::units::{Length, LengthUnit, Unit, MILLIMETERS, CENTIMETERS, METERS, KILOMETERS, INCHES, FEET, YARDS, MILES};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UnitSystem {
+    Metric,
+    Imperial
+}
+
+impl UnitSystem {
+
+    /// The units of the system, smallest first.
+    pub fn units(&self) -> Vec<LengthUnit> {
+        match *self {
+            UnitSystem::Metric => vec![MILLIMETERS, CENTIMETERS, METERS, KILOMETERS],
+            UnitSystem::Imperial => vec![INCHES, FEET, YARDS, MILES]
+        }
+    }
+
+    /// Returns the system that `unit` belongs to, or `None` for units outside of both systems.
+    pub fn of(unit: &LengthUnit) -> Option<UnitSystem> {
+        [UnitSystem::Metric, UnitSystem::Imperial].iter()
+            .find(|system| system.units().contains(unit))
+            .cloned()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Precision {
+    Decimals(usize),
+    SignificantFigures(usize)
+}
+
+impl Precision {
+
+    pub fn format_value(&self, value: f64) -> String {
+        match *self {
+            Precision::Decimals(decimals) => format!("{:.*}", decimals, value),
+            Precision::SignificantFigures(figures) => {
+                if value == 0.0 || !value.is_finite() {
+                    return format!("{}", value);
+                }
+                let figures = figures.max(1) as i32;
+                let magnitude = value.abs().log10().floor() as i32;
+                if magnitude >= figures {
+                    let step = 10f64.powi(magnitude + 1 - figures);
+                    return format!("{:.0}", (value / step).round() * step);
+                }
+                let decimals = (figures - 1 - magnitude).max(0) as usize;
+                format!("{:.*}", decimals, value)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DisplayFormat {
+    pub system: UnitSystem,
+    pub precision: Precision
+}
+
+impl DisplayFormat {
+
+    pub fn new(system: UnitSystem, precision: Precision) -> DisplayFormat {
+        DisplayFormat { system: system, precision: precision }
+    }
+
+    /// Picks the largest unit of the system in which the length is at least one, falling back
+    /// to the smallest unit for lengths shorter than that.
+    pub fn best_unit(&self, length: &Length) -> LengthUnit {
+        let units = self.system.units();
+        let smallest = units[0].clone();
+        units.into_iter().rev()
+            .find(|unit| length.value(unit).abs() >= 1.0)
+            .unwrap_or(smallest)
+    }
+
+    pub fn scale(&self, length: &Length) -> Length {
+        length.convert_to(self.best_unit(length))
+    }
+
+    pub fn format(&self, length: &Length) -> String {
+        let scaled = self.scale(length);
+        format!("{} {}", self.precision.format_value(scaled.value), scaled.unit.abbrev())
+    }
+
+    /// Like `format`, with the separators of `locale`.
+    pub fn format_localized(&self, length: &Length, locale: &NumberLocale) -> String {
+        let scaled = self.scale(length);
+        format!("{} {}", locale.localize(&self.precision.format_value(scaled.value)), scaled.unit.abbrev())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::locale::GERMAN;
+    use ::estimator::units::{Length, CENTIMETERS, GAUGE, INCHES, METERS, YARDS};
+
+    #[test]
+    fn metric_format_should_pick_most_readable_unit() {
+        let format = DisplayFormat::new(UnitSystem::Metric, Precision::Decimals(2));
+        assert_eq!("1.23 km", format.format(&Length::new(123456.78, CENTIMETERS)));
+        assert_eq!("4.50 mm", format.format(&Length::new(0.45, CENTIMETERS)));
+        assert_eq!("12.00 m", format.format(&Length::new(12.0, METERS)));
+        assert_eq!("1,23 km", format.format_localized(&Length::new(123456.78, CENTIMETERS), &GERMAN));
+    }
+
+    #[test]
+    fn imperial_format_should_pick_most_readable_unit() {
+        let format = DisplayFormat::new(UnitSystem::Imperial, Precision::Decimals(1));
+        assert_eq!("9.0 in", format.format(&Length::new(0.25, YARDS)));
+        assert_eq!("2.0 ft", format.format(&Length::new(24.0, INCHES)));
+        assert_eq!("150.0 yrd", format.format(&Length::new(150.0, YARDS)));
+    }
+
+    #[test]
+    fn significant_figures_should_scale_decimals_with_magnitude() {
+        let precision = Precision::SignificantFigures(3);
+        assert_eq!("123000", precision.format_value(123456.78));
+        assert_eq!("-4570", precision.format_value(-4567.0));
+        assert_eq!("457", precision.format_value(456.7));
+        assert_eq!("1.23", precision.format_value(1.23456));
+        assert_eq!("0.00457", precision.format_value(0.004567));
+        assert_eq!("0", precision.format_value(0.0));
+    }
+
+    #[test]
+    fn unit_system_should_be_found_for_builtin_units_only() {
+        assert_eq!(Some(UnitSystem::Metric), UnitSystem::of(&CENTIMETERS));
+        assert_eq!(Some(UnitSystem::Imperial), UnitSystem::of(&YARDS));
+        assert_eq!(None, UnitSystem::of(&GAUGE));
+    }
+}
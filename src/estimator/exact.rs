@@ -0,0 +1,231 @@
+//! Exact lengths, stored as a whole number of nanometers. Every built in unit is a whole number
+//! of nanometers (an inch is 25,400,000 nm and a gauge is 254 nm), so sums, differences and
+//! comparisons of values entered in those units are exact, and converting inches to centimeters
+//! and back returns the value that was entered. Multiplying or dividing by an `f64` rounds the
+//! result to the nearest nanometer.
+
+use std::ops::{Add, Sub, Mul, Div};
+
+use super::units::{Length, LengthUnit, Unit};
+
+const NANOMETERS_PER_METER: f64 = 1_000_000_000.0;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct ExactLength {
+    pub nanometers: i64
+}
+
+/// The number of nanometers in one `unit`, rounded to the nearest nanometer. Returns `None` for
+/// a unit that rounds to zero nanometers or is too large to represent, since lengths in it can't
+/// be stored exactly.
+pub fn nanometers_per_unit(unit: &LengthUnit) -> Option<i64> {
+    let nanometers = (unit.value_to_reference(1.0) * NANOMETERS_PER_METER).round();
+    if nanometers >= 1.0 && nanometers < i64::MAX as f64 {
+        Some(nanometers as i64)
+    } else {
+        None
+    }
+}
+
+impl ExactLength {
+
+    pub fn from_nanometers(nanometers: i64) -> ExactLength {
+        ExactLength { nanometers: nanometers }
+    }
+
+    pub fn zero() -> ExactLength {
+        ExactLength::from_nanometers(0)
+    }
+
+    /// Converts a floating point value, rounding to the nearest nanometer. Returns `None` if the
+    /// value is not finite, too large to represent, or in a unit smaller than a nanometer.
+    pub fn new(value: f64, unit: &LengthUnit) -> Option<ExactLength> {
+        nanometers_per_unit(unit).and_then(|per_unit| {
+            let nanometers = (value * per_unit as f64).round();
+            if nanometers.is_finite() && nanometers.abs() < i64::MAX as f64 {
+                Some(ExactLength::from_nanometers(nanometers as i64))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn from_length(length: &Length) -> Option<ExactLength> {
+        ExactLength::new(length.value, &length.unit)
+    }
+
+    /// Parses a decimal string such as `-12.375` without going through `f64`, so values with a
+    /// finite decimal representation in `unit` are stored exactly. Returns `None` for invalid
+    /// input, values too large to represent, or a unit smaller than a nanometer.
+    pub fn parse(input: &str, unit: &LengthUnit) -> Option<ExactLength> {
+        let trimmed = input.trim();
+        let (negative, digits) = if trimmed.starts_with('-') {
+            (true, &trimmed[1..])
+        } else if trimmed.starts_with('+') {
+            (false, &trimmed[1..])
+        } else {
+            (false, trimmed)
+        };
+
+        let mut parts = digits.splitn(2, '.');
+        let whole = parts.next().unwrap_or("");
+        let fraction = parts.next().unwrap_or("");
+        if (whole.is_empty() && fraction.is_empty()) ||
+                !whole.chars().chain(fraction.chars()).all(|ch| ch.is_digit(10)) {
+            return None;
+        }
+
+        let numerator_opt = whole.chars().chain(fraction.chars()).fold(Some(0i64), |acc, ch| {
+            acc.and_then(|val| val.checked_mul(10))
+                .and_then(|val| val.checked_add(ch.to_digit(10).unwrap() as i64))
+        });
+        let denominator_opt = 10i64.checked_pow(fraction.len() as u32);
+
+        let scaled_opt = numerator_opt.and_then(|numerator| {
+            nanometers_per_unit(unit).and_then(|per_unit| numerator.checked_mul(per_unit))
+        });
+        scaled_opt.and_then(|scaled| {
+            denominator_opt.map(|denominator| {
+                let nanometers = divide_rounded(scaled, denominator);
+                ExactLength::from_nanometers(if negative { -nanometers } else { nanometers })
+            })
+        })
+    }
+
+    pub fn value(&self, unit: &LengthUnit) -> f64 {
+        match nanometers_per_unit(unit) {
+            Some(per_unit) => self.nanometers as f64 / per_unit as f64,
+            None => unit.value_from_reference(self.nanometers as f64 / NANOMETERS_PER_METER)
+        }
+    }
+
+    pub fn to_length(&self, unit: LengthUnit) -> Length {
+        Length::new(self.value(&unit), unit)
+    }
+
+    pub fn format(&self, unit: LengthUnit) -> String {
+        self.to_length(unit).format()
+    }
+}
+
+impl Length {
+
+    pub fn to_exact(&self) -> Option<ExactLength> {
+        ExactLength::from_length(self)
+    }
+}
+
+/// Integer division rounding half away from zero.
+fn divide_rounded(numerator: i64, denominator: i64) -> i64 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder.abs() * 2 >= denominator.abs() {
+        quotient + numerator.signum() * denominator.signum()
+    } else {
+        quotient
+    }
+}
+
+/// Returns `None` if the sum overflows.
+impl Add for ExactLength {
+    type Output = Option<ExactLength>;
+
+    fn add(self, other: ExactLength) -> Option<ExactLength> {
+        self.nanometers.checked_add(other.nanometers).map(ExactLength::from_nanometers)
+    }
+}
+
+/// Returns `None` if the difference overflows.
+impl Sub for ExactLength {
+    type Output = Option<ExactLength>;
+
+    fn sub(self, other: ExactLength) -> Option<ExactLength> {
+        self.nanometers.checked_sub(other.nanometers).map(ExactLength::from_nanometers)
+    }
+}
+
+impl Mul<f64> for ExactLength {
+    type Output = ExactLength;
+
+    fn mul(self, other: f64) -> ExactLength {
+        ExactLength::from_nanometers((self.nanometers as f64 * other).round() as i64)
+    }
+}
+
+impl Div<f64> for ExactLength {
+    type Output = ExactLength;
+
+    fn div(self, divisor: f64) -> ExactLength {
+        ExactLength::from_nanometers((self.nanometers as f64 / divisor).round() as i64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::units::{Length, CENTIMETERS, GAUGE, INCHES, METERS, YARDS};
+
+    #[test]
+    fn round_trip_through_other_units_should_be_exact() {
+        let original = ExactLength::parse("66.11", &INCHES).unwrap();
+        let in_cm = ExactLength::new(original.value(&CENTIMETERS), &CENTIMETERS).unwrap();
+        assert_eq!(original, in_cm);
+        assert_eq!(66.11, in_cm.value(&INCHES));
+    }
+
+    #[test]
+    fn equal_lengths_in_different_units_should_be_equal() {
+        assert_eq!(ExactLength::new(36.0, &INCHES), ExactLength::new(1.0, &YARDS));
+        assert_eq!(ExactLength::new(100.0, &GAUGE), ExactLength::parse("0.001", &INCHES));
+        assert!(ExactLength::new(1.0, &YARDS).unwrap() < ExactLength::new(1.0, &METERS).unwrap());
+    }
+
+    #[test]
+    fn decimal_sums_should_not_accumulate_error() {
+        let sum = (ExactLength::parse("0.1", &CENTIMETERS).unwrap() + ExactLength::parse("0.2", &CENTIMETERS).unwrap()).unwrap();
+        assert_eq!(ExactLength::parse("0.3", &CENTIMETERS).unwrap(), sum);
+        assert_eq!(ExactLength::parse("-0.1", &CENTIMETERS), ExactLength::parse("0.2", &CENTIMETERS).unwrap() - sum);
+    }
+
+    #[test]
+    fn scaling_should_round_to_the_nearest_nanometer() {
+        let third = ExactLength::new(1.0, &METERS).unwrap() / 3.0;
+        assert_eq!(333_333_333, third.nanometers);
+        assert_eq!(ExactLength::new(1.0, &METERS), third * 3.0 + ExactLength::from_nanometers(1));
+    }
+
+    #[test]
+    fn parse_should_reject_invalid_input() {
+        assert!(ExactLength::parse("", &INCHES).is_none());
+        assert!(ExactLength::parse("1.2.3", &INCHES).is_none());
+        assert!(ExactLength::parse("abc", &INCHES).is_none());
+        assert!(ExactLength::parse("99999999999999", &INCHES).is_none());
+        assert_eq!(Length::new(-2.5, CENTIMETERS).to_exact(), ExactLength::parse("-2.5", &CENTIMETERS));
+    }
+
+    #[test]
+    fn new_should_reject_values_that_cannot_be_represented() {
+        assert!(ExactLength::new(::std::f64::NAN, &METERS).is_none());
+        assert!(ExactLength::new(::std::f64::INFINITY, &METERS).is_none());
+        assert!(ExactLength::new(1.0e12, &METERS).is_none());
+        assert!(ExactLength::new(-1.0e9, &METERS).is_some());
+    }
+
+    #[test]
+    fn sums_and_differences_should_report_overflow() {
+        let max = ExactLength::from_nanometers(::std::i64::MAX);
+        let min = ExactLength::from_nanometers(::std::i64::MIN);
+        assert!((max + ExactLength::from_nanometers(1)).is_none());
+        assert!((min - ExactLength::from_nanometers(1)).is_none());
+        assert_eq!(Some(ExactLength::zero()), max - max);
+    }
+
+    #[test]
+    fn units_smaller_than_a_nanometer_should_be_rejected() {
+        let picometers = LengthUnit::new("Picometers", "pm", 1.0e-12);
+        assert_eq!(None, nanometers_per_unit(&picometers));
+        assert!(ExactLength::new(1.0, &picometers).is_none());
+        assert!(ExactLength::parse("1000", &picometers).is_none());
+        assert!((ExactLength::from_nanometers(1).value(&picometers) - 1000.0).abs() < 0.000001);
+    }
+}
@@ -0,0 +1,138 @@
+//! Locale-aware reading and writing of numbers, so values can be typed as `0,08` and results
+//! shown as `1.234,56 m` where that is the convention.
+
+use super::units::{Length, LengthUnit, Unit};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct NumberLocale {
+    pub name: &'static str,
+    pub decimal_separator: char,
+    pub thousands_separator: char
+}
+
+pub const ENGLISH: NumberLocale = NumberLocale { name: "English (1,234.56)", decimal_separator: '.', thousands_separator: ',' };
+pub const GERMAN: NumberLocale = NumberLocale { name: "Deutsch (1.234,56)", decimal_separator: ',', thousands_separator: '.' };
+pub const FRENCH: NumberLocale = NumberLocale { name: "Français (1 234,56)", decimal_separator: ',', thousands_separator: ' ' };
+
+pub const LOCALES: [NumberLocale; 3] = [ENGLISH, GERMAN, FRENCH];
+
+impl NumberLocale {
+
+    /// Looks up a locale by language code, e.g. `de` or `fr-FR`.
+    pub fn from_code(code: &str) -> Option<NumberLocale> {
+        let language = code.trim().split(|ch| ch == '-' || ch == '_').next().unwrap_or("").to_lowercase();
+        match language.as_str() {
+            "en" => Some(ENGLISH),
+            "de" => Some(GERMAN),
+            "fr" => Some(FRENCH),
+            _ => None
+        }
+    }
+
+    /// Parses a number written in this locale. Thousands separators are optional, but when
+    /// present they must separate groups of three digits, so `0,08` is not read as `8` in
+    /// English. A no-break space is accepted in place of a space separator.
+    pub fn parse_number(&self, input: &str) -> Option<f64> {
+        let trimmed = input.trim();
+        let (sign, unsigned) = if trimmed.starts_with('-') || trimmed.starts_with('+') {
+            trimmed.split_at(1)
+        } else {
+            ("", trimmed)
+        };
+
+        let mut parts = unsigned.splitn(2, self.decimal_separator);
+        let whole = parts.next().unwrap_or("");
+        let fraction = parts.next().unwrap_or("");
+
+        let is_separator = |ch: char| ch == self.thousands_separator || (self.thousands_separator == ' ' && ch == '\u{a0}');
+        let groups = whole.split(is_separator).collect::<Vec<&str>>();
+        let grouping_ok = groups.len() == 1 ||
+                (groups[0].len() >= 1 && groups[0].len() <= 3 && groups[1..].iter().all(|group| group.len() == 3));
+        let digits_ok = groups.iter().chain(Some(&fraction)).all(|part| part.chars().all(|ch| ch.is_digit(10)));
+        if !grouping_ok || !digits_ok || (whole.is_empty() && fraction.is_empty()) {
+            return None;
+        }
+
+        format!("{}{}.{}", sign, groups.concat(), fraction).parse::<f64>().ok()
+    }
+
+    pub fn parse_length(&self, input: &str, unit: LengthUnit) -> Option<Length> {
+        self.parse_number(input).map(|value| Length::new(value, unit))
+    }
+
+    /// Rewrites a number formatted by Rust, such as `-1234.5`, with this locale's separators.
+    pub fn localize(&self, formatted: &str) -> String {
+        let (sign, unsigned) = if formatted.starts_with('-') { formatted.split_at(1) } else { ("", formatted) };
+        let mut parts = unsigned.splitn(2, '.');
+        let whole = parts.next().unwrap_or("");
+
+        let mut grouped = String::new();
+        for (idx, ch) in whole.chars().enumerate() {
+            if idx > 0 && (whole.len() - idx) % 3 == 0 {
+                grouped.push(self.thousands_separator);
+            }
+            grouped.push(ch);
+        }
+
+        match parts.next() {
+            Some(fraction) => format!("{}{}{}{}", sign, grouped, self.decimal_separator, fraction),
+            None => format!("{}{}", sign, grouped)
+        }
+    }
+
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        self.localize(&format!("{:.*}", decimals, value))
+    }
+
+    /// Formats a length like `Length::format`, with this locale's separators.
+    pub fn format_length(&self, length: &Length) -> String {
+        format!("{} {}", self.format_number(length.value, 2), length.unit.abbrev())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::units::{Length, CENTIMETERS, METERS};
+
+    #[test]
+    fn decimal_comma_should_be_parsed_in_german_and_french() {
+        assert_eq!(Some(0.08), GERMAN.parse_number("0,08"));
+        assert_eq!(Some(0.08), FRENCH.parse_number(" 0,08 "));
+        assert_eq!(Some(Length::new(12.5, CENTIMETERS)), GERMAN.parse_length("12,5", CENTIMETERS));
+    }
+
+    #[test]
+    fn thousands_separators_should_be_accepted_when_grouped_correctly() {
+        assert_eq!(Some(1234.56), ENGLISH.parse_number("1,234.56"));
+        assert_eq!(Some(-1234567.5), GERMAN.parse_number("-1.234.567,5"));
+        assert_eq!(Some(1234.56), FRENCH.parse_number("1 234,56"));
+        assert_eq!(Some(1234.56), FRENCH.parse_number("1\u{a0}234,56"));
+        assert_eq!(Some(1234.56), GERMAN.parse_number("1234,56"));
+    }
+
+    #[test]
+    fn misplaced_separators_should_be_rejected() {
+        assert_eq!(None, ENGLISH.parse_number("0,08"));
+        assert_eq!(None, GERMAN.parse_number("1.23,4"));
+        assert_eq!(None, GERMAN.parse_number("1,2,3"));
+        assert_eq!(None, ENGLISH.parse_number(""));
+        assert_eq!(None, ENGLISH.parse_number("12abc"));
+    }
+
+    #[test]
+    fn lengths_should_be_formatted_with_locale_separators() {
+        let length = Length::new(1234.556, METERS);
+        assert_eq!("1,234.56 m", ENGLISH.format_length(&length));
+        assert_eq!("1.234,56 m", GERMAN.format_length(&length));
+        assert_eq!("1 234,56 m", FRENCH.format_length(&length));
+        assert_eq!("-123,00 m", GERMAN.format_length(&Length::new(-123.0, METERS)));
+    }
+
+    #[test]
+    fn locale_should_be_found_by_language_code() {
+        assert_eq!(Some(GERMAN), NumberLocale::from_code("de-DE"));
+        assert_eq!(Some(FRENCH), NumberLocale::from_code("fr"));
+        assert_eq!(None, NumberLocale::from_code("xx"));
+    }
+}
@@ -0,0 +1,157 @@
+//! Macros that define a unit type and a quantity measured in it. `Length` and the quantities in
+//! `quantities` are all built from these, so they share one implementation of conversion,
+//! comparison and arithmetic.
+
+/// Defines a unit whose values convert to a reference unit by a constant factor. Names are
+/// `Cow`s so that units can be declared as constants or created at runtime.
+macro_rules! unit {
+    ($unit:ident) => {
+        #[derive(Debug, PartialEq, Clone)]
+        pub struct $unit {
+            full_name: Cow<'static, str>,
+            abbrev: Cow<'static, str>,
+            factor_to_reference: f64
+        }
+
+        impl $unit {
+
+            /// Creates a unit at runtime. `factor_to_reference` is the number of reference
+            /// units in one unit.
+            pub fn new(full_name: &str, abbrev: &str, factor_to_reference: f64) -> $unit {
+                $unit {
+                    full_name: Cow::Owned(full_name.to_string()),
+                    abbrev: Cow::Owned(abbrev.to_string()),
+                    factor_to_reference: factor_to_reference
+                }
+            }
+        }
+
+        impl Unit for $unit {
+
+            fn abbrev(&self) -> &str {
+                &self.abbrev
+            }
+
+            fn full_name(&self) -> &str {
+                &self.full_name
+            }
+
+            fn value_to_reference(&self, value: f64) -> f64 {
+                self.factor_to_reference * value
+            }
+
+            fn value_from_reference(&self, value: f64) -> f64 {
+                value / self.factor_to_reference
+            }
+        }
+    }
+}
+
+/// Defines a quantity as a value in one of its units, with conversion between units and the
+/// arithmetic that keeps it the same kind of quantity. Operands in different units are converted
+/// to the unit of the left-hand side.
+macro_rules! quantity {
+    ($quantity:ident, $unit:ident) => {
+        #[derive(Debug, PartialEq, Clone)]
+        pub struct $quantity {
+            pub value: f64,
+            pub unit: $unit
+        }
+
+        impl $quantity {
+
+            pub fn new(value: f64, unit: $unit) -> $quantity {
+                $quantity { value: value, unit: unit }
+            }
+
+            pub fn value(&self, unit: &$unit) -> f64 {
+                if self.unit == *unit {
+                    self.value
+                } else {
+                    unit.value_from_reference(self.unit.value_to_reference(self.value))
+                }
+            }
+
+            pub fn convert_to(&self, unit: $unit) -> $quantity {
+                let converted_value = self.value(&unit);
+                $quantity { value: converted_value, unit: unit }
+            }
+
+            pub fn format(&self) -> String {
+                format!("{:.2} {}", self.value, self.unit.abbrev())
+            }
+        }
+
+        impl Quantity for $quantity {
+            fn reference_value(&self) -> f64 {
+                self.unit.value_to_reference(self.value)
+            }
+        }
+
+        impl PartialOrd for $quantity {
+            fn partial_cmp(&self, other: &$quantity) -> Option<Ordering> {
+                self.value.partial_cmp(&other.value(&self.unit))
+            }
+        }
+
+        impl Add for $quantity {
+            type Output = $quantity;
+
+            fn add(self, other: $quantity) -> $quantity {
+                let other_value = other.value(&self.unit);
+                $quantity::new(self.value + other_value, self.unit)
+            }
+        }
+
+        impl Sub for $quantity {
+            type Output = $quantity;
+
+            fn sub(self, other: $quantity) -> $quantity {
+                let other_value = other.value(&self.unit);
+                $quantity::new(self.value - other_value, self.unit)
+            }
+        }
+
+        impl Mul<f64> for $quantity {
+            type Output = $quantity;
+
+            fn mul(self, other: f64) -> $quantity {
+                $quantity::new(self.value * other, self.unit)
+            }
+        }
+
+        impl Div<f64> for $quantity {
+            type Output = $quantity;
+
+            fn div(self, divisor: f64) -> $quantity {
+                $quantity::new(self.value / divisor, self.unit)
+            }
+        }
+    }
+}
+
+/// Implements `$lhs * $rhs = $output`, with the result in the output's reference unit.
+macro_rules! product {
+    ($lhs:ident * $rhs:ident = $output:ident in $reference:ident) => {
+        impl Mul<$rhs> for $lhs {
+            type Output = $output;
+
+            fn mul(self, other: $rhs) -> $output {
+                $output::new(self.reference_value() * other.reference_value(), $reference)
+            }
+        }
+    }
+}
+
+/// Implements `$lhs / $rhs = $output`, with the result in the output's reference unit.
+macro_rules! quotient {
+    ($lhs:ident / $rhs:ident = $output:ident in $reference:ident) => {
+        impl Div<$rhs> for $lhs {
+            type Output = $output;
+
+            fn div(self, other: $rhs) -> $output {
+                $output::new(self.reference_value() / other.reference_value(), $reference)
+            }
+        }
+    }
+}
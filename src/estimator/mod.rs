@@ -1,4 +1,20 @@
+#[macro_use]
+mod macros;
+
 pub mod units;
+pub mod display;
+pub mod exact;
+pub mod locale;
+pub mod quantities;
+pub mod comparison;
+pub mod cost;
+pub mod planner;
+pub mod cutlist;
+pub mod slitting;
+pub mod rewind;
+pub mod runtime;
+pub mod simulation;
+pub mod plies;
 
 #[cfg(test)]
 mod test;
@@ -6,9 +22,16 @@ mod test;
 use std::f64::consts::PI;
 
 pub use self::units::*;
+use self::plies::PlyStack;
+use self::quantities::{Area, BasisWeight, Mass};
 
 pub const EPSILON: f64 = 0.000016f64;
 
+/// How close, in nanometers, a diameter must come to a layer boundary to count as reaching it.
+/// Rounding the core, diameter and thickness to the nanometer can each be off by half of one.
+const LAYER_TOLERANCE_NM: i64 = 2;
+
+#[derive(Debug, Clone)]
 pub struct MaterialRoll {
     pub id: Length,
     pub od: Length,
@@ -17,18 +40,156 @@ pub struct MaterialRoll {
 
 impl MaterialRoll {
 
+    /// Builds the roll that results from winding `length` of material onto a core of diameter
+    /// `id`. Returns `None` if the core or thickness is not positive.
+    pub fn from_length(id: Length, thickness: Length, length: &Length) -> Option<MaterialRoll> {
+        let unit: LengthUnit = CENTIMETERS;
+        let id_val = id.value(&unit);
+        let thickness_val = thickness.value(&unit);
+
+        if id_val <= 0.0 || thickness_val <= 0.0 {
+            return None;
+        }
+
+        let (layers, fraction) = layers_for_length(id_val, thickness_val, length.value(&unit));
+        Some(MaterialRoll {
+            id: id,
+            od: Length::new(id_val + (layers as f64 + fraction) * thickness_val, unit),
+            thickness: thickness
+        })
+    }
+
+    /// Builds a roll whose wraps are made of every ply in `stack`, so each wrap is as thick as
+    /// the plies combined.
+    pub fn from_plies(id: Length, od: Length, stack: &PlyStack) -> MaterialRoll {
+        MaterialRoll {
+            id: id,
+            od: od,
+            thickness: stack.combined_thickness()
+        }
+    }
+
     pub fn get_roll_length(&self) -> Length {
+        self.get_length_at_diameter(&self.od)
+    }
+
+    /// Weight of the whole roll, from the width of the material and its basis weight.
+    pub fn get_weight(&self, width: Length, basis_weight: BasisWeight) -> Mass {
+        let area: Area = self.get_roll_length() * width;
+        area * basis_weight
+    }
+
+    /// Returns the length of material wound between the core and the given diameter. A layer
+    /// that is only partly wound counts in proportion to how far it reaches, so the length grows
+    /// smoothly with the diameter. Diameters larger than the roll's OD are treated as the OD.
+    pub fn get_length_at_diameter(&self, diameter: &Length) -> Length {
         let unit: LengthUnit = CENTIMETERS;
-        let mut id_val = self.id.value(&unit);
-        let od = self.od.value(&unit);
+        let (layers, fraction) = self.split_layers(diameter).unwrap_or((0, 0.0));
+        let length = wound_length(self.id.value(&unit), self.thickness.value(&unit), layers, fraction);
+        Length::new(length, unit)
+    }
+
+    /// Returns the diameter the roll has once the given length has been wound onto the core, or
+    /// `None` if the length is more than the roll holds. This is the inverse of
+    /// `get_length_at_diameter`.
+    pub fn get_diameter_at_length(&self, length: &Length) -> Option<Length> {
+        let unit: LengthUnit = CENTIMETERS;
+        let id = self.id.value(&unit);
         let thickness = self.thickness.value(&unit);
-        let mut length: f64 = 0.0;
+        let remaining = length.value(&unit);
 
-        while id_val < od {
-            length += id_val * PI;
-            id_val += thickness;
+        if remaining > self.get_roll_length().value(&unit) + EPSILON {
+            return None;
+        }
+        if thickness <= 0.0 {
+            return Some(self.id.convert_to(unit));
         }
 
-        Length::new(length, unit)
+        let (layers, fraction) = layers_for_length(id, thickness, remaining);
+        let diameter = id + (layers as f64 + fraction) * thickness;
+        Some(Length::new(diameter.min(self.od.value(&unit)), unit))
+    }
+
+    /// Samples cumulative length at evenly spaced diameters from the ID to the OD. Each entry is
+    /// a `(diameter, length)` pair, and both endpoints are always included.
+    pub fn get_length_curve(&self, samples: usize) -> Vec<(Length, Length)> {
+        let unit: LengthUnit = CENTIMETERS;
+        let id = self.id.value(&unit);
+        let od = self.od.value(&unit);
+        let intervals = if samples < 2 { 1 } else { samples - 1 };
+        let step = (od - id) / intervals as f64;
+
+        (0..intervals + 1).map(|i| {
+            let diameter = Length::new(id + step * i as f64, unit.clone());
+            let length = self.get_length_at_diameter(&diameter);
+            (diameter, length)
+        }).collect()
+    }
+
+    /// Counts every layer that has been started, including a partly wound outer layer.
+    pub fn get_layer_count(&self) -> u32 {
+        match self.split_layers(&self.od) {
+            Some((layers, fraction)) => layers as u32 + if fraction > 0.0 { 1 } else { 0 },
+            None => 0
+        }
+    }
+
+    /// Splits the distance from the core to `diameter` (capped at the OD) into whole layers and
+    /// the fraction of a layer beyond them. The distances are compared as exact lengths, so a
+    /// roll spanning a whole number of layers isn't given a sliver of an extra one by floating
+    /// point error. Returns `None` if a dimension can't be represented or the thickness rounds
+    /// to zero.
+    fn split_layers(&self, diameter: &Length) -> Option<(u64, f64)> {
+        let end = if *diameter < self.od { diameter } else { &self.od };
+        let (span, thickness) = match (self.id.to_exact(), end.to_exact(), self.thickness.to_exact()) {
+            (Some(id), Some(end), Some(thickness)) if thickness.nanometers > 0 => match end - id {
+                Some(span) => (span, thickness),
+                None => return None
+            },
+            _ => return None
+        };
+        if span.nanometers <= LAYER_TOLERANCE_NM {
+            return Some((0, 0.0));
+        }
+
+        let layers = (span.nanometers + LAYER_TOLERANCE_NM) / thickness.nanometers;
+        let remainder = span.nanometers - layers * thickness.nanometers;
+        let fraction = if remainder > LAYER_TOLERANCE_NM { remainder as f64 / thickness.nanometers as f64 } else { 0.0 };
+        Some((layers as u64, fraction))
+    }
+}
+
+/// Circumference, in the units of `id` and `thickness`, of the layer `layer` wraps out from the
+/// core. Every conversion between lengths and layers goes through this, so they agree exactly.
+fn layer_length(id: f64, thickness: f64, layer: u64) -> f64 {
+    (id + layer as f64 * thickness) * PI
+}
+
+/// Length of `layers` whole layers plus `fraction` of the next one.
+fn wound_length(id: f64, thickness: f64, layers: u64, fraction: f64) -> f64 {
+    let mut length: f64 = 0.0;
+    for layer in 0..layers {
+        length += layer_length(id, thickness, layer);
+    }
+    length + layer_length(id, thickness, layers) * fraction
+}
+
+/// The inverse of `wound_length`: the whole layers that `length` fills and the fraction of the
+/// next one. A length within `EPSILON` of filling a layer fills it, just as `split_layers`
+/// rounds diameters within its tolerance of a boundary, so round trips land on the same layer.
+/// `thickness` must be positive.
+fn layers_for_length(id: f64, thickness: f64, length: f64) -> (u64, f64) {
+    let mut layers: u64 = 0;
+    let mut remaining = length;
+    loop {
+        let current = layer_length(id, thickness, layers);
+        if remaining < current - EPSILON {
+            return (layers, (remaining / current).max(0.0));
+        }
+        remaining -= current;
+        layers += 1;
+        if remaining <= EPSILON {
+            return (layers, 0.0);
+        }
     }
 }
@@ -0,0 +1,155 @@
+use super::MaterialRoll;
+use super::units::{Length, LengthUnit, CENTIMETERS};
+
+/// What a job needs from the rolls: either a continuous length, or a number of pieces that
+/// are each cut from a single roll.
+#[derive(Debug, PartialEq, Clone)]
+pub enum JobRequirement {
+    TotalLength(Length),
+    Pieces { count: u32, cut_length: Length }
+}
+
+/// How many rolls of a given spec a job needs. All lengths are in centimeters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct JobPlan {
+    pub rolls_required: u32,
+    pub leftover_on_last_roll: Length,
+    pub splice_count: u32,
+    pub splice_waste: Length,
+    pub pieces_per_roll: Option<u32>,
+    pub offcut_waste: Length
+}
+
+/// Plans a job against full rolls of the given spec. For continuous jobs every splice between
+/// rolls loses `splice_allowance` of material. Piece jobs are not spliced; instead the tail of
+/// each full roll that is too short for another piece is counted as offcut waste.
+///
+/// Returns `None` if a roll cannot make progress on the job, i.e. the job needs splicing but a
+/// roll is shorter than the splice allowance, or a roll is shorter than the cut length.
+pub fn plan_job(roll: &MaterialRoll, requirement: &JobRequirement, splice_allowance: &Length) -> Option<JobPlan> {
+    let unit: LengthUnit = CENTIMETERS;
+    let roll_length = roll.get_roll_length().value(&unit);
+
+    match *requirement {
+        JobRequirement::TotalLength(ref required) => {
+            let required = required.value(&unit);
+            let splice = splice_allowance.value(&unit);
+            let usable_after_splice = roll_length - splice;
+            if roll_length <= 0.0 {
+                return None;
+            }
+
+            let rolls_required = if required <= roll_length {
+                1
+            } else if usable_after_splice <= 0.0 {
+                return None;
+            } else {
+                1 + ((required - roll_length) / usable_after_splice).ceil() as u32
+            };
+            let splice_count = rolls_required - 1;
+            let supplied = roll_length + usable_after_splice * splice_count as f64;
+
+            Some(JobPlan {
+                rolls_required: rolls_required,
+                leftover_on_last_roll: Length::new(supplied - required, unit.clone()),
+                splice_count: splice_count,
+                splice_waste: Length::new(splice * splice_count as f64, unit.clone()),
+                pieces_per_roll: None,
+                offcut_waste: Length::new(0.0, unit)
+            })
+        }
+        JobRequirement::Pieces { count, ref cut_length } => {
+            let cut = cut_length.value(&unit);
+            if cut <= 0.0 {
+                return None;
+            }
+
+            let pieces_per_roll = (roll_length / cut).floor() as u32;
+            if pieces_per_roll == 0 {
+                return None;
+            }
+
+            // Rounded up in u64, as `count + pieces_per_roll` can overflow a u32
+            let rolls_required = ((count as u64 + pieces_per_roll as u64 - 1) / pieces_per_roll as u64).max(1) as u32;
+            let pieces_on_last_roll = count - (rolls_required - 1) * pieces_per_roll;
+            let offcut_per_roll = roll_length - pieces_per_roll as f64 * cut;
+
+            Some(JobPlan {
+                rolls_required: rolls_required,
+                leftover_on_last_roll: Length::new(roll_length - pieces_on_last_roll as f64 * cut, unit.clone()),
+                splice_count: 0,
+                splice_waste: Length::new(0.0, unit.clone()),
+                pieces_per_roll: Some(pieces_per_roll),
+                offcut_waste: Length::new(offcut_per_roll * (rolls_required - 1) as f64, unit)
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::MaterialRoll;
+    use ::estimator::test::roll_holding;
+    use ::estimator::units::{Length, CENTIMETERS};
+
+    const EPSILON: f64 = 0.00001;
+
+    // A roll holding exactly 100 cm: a single 1 cm layer.
+    fn roll() -> MaterialRoll {
+        roll_holding(100.0, 1, 1.0)
+    }
+
+    #[test]
+    fn job_shorter_than_a_roll_should_need_one_roll() {
+        let requirement = JobRequirement::TotalLength(Length::new(60.0, CENTIMETERS));
+        let plan = plan_job(&roll(), &requirement, &Length::new(5.0, CENTIMETERS)).unwrap();
+        assert_eq!(1, plan.rolls_required);
+        assert_eq!(0, plan.splice_count);
+        assert!((plan.leftover_on_last_roll.value - 40.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn continuous_job_should_account_for_splice_waste() {
+        let requirement = JobRequirement::TotalLength(Length::new(250.0, CENTIMETERS));
+        let plan = plan_job(&roll(), &requirement, &Length::new(10.0, CENTIMETERS)).unwrap();
+        // 100 + 90 + 90 = 280 usable across three rolls
+        assert_eq!(3, plan.rolls_required);
+        assert_eq!(2, plan.splice_count);
+        assert!((plan.splice_waste.value - 20.0).abs() < EPSILON);
+        assert!((plan.leftover_on_last_roll.value - 30.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn job_that_fits_on_one_roll_should_not_need_splice_allowance() {
+        let requirement = JobRequirement::TotalLength(Length::new(60.0, CENTIMETERS));
+        let plan = plan_job(&roll(), &requirement, &Length::new(150.0, CENTIMETERS)).unwrap();
+        assert_eq!(1, plan.rolls_required);
+
+        let requirement = JobRequirement::TotalLength(Length::new(160.0, CENTIMETERS));
+        assert!(plan_job(&roll(), &requirement, &Length::new(150.0, CENTIMETERS)).is_none());
+    }
+
+    #[test]
+    fn piece_job_should_not_split_pieces_across_rolls() {
+        let requirement = JobRequirement::Pieces { count: 7, cut_length: Length::new(30.0, CENTIMETERS) };
+        let plan = plan_job(&roll(), &requirement, &Length::new(10.0, CENTIMETERS)).unwrap();
+        assert_eq!(Some(3), plan.pieces_per_roll);
+        assert_eq!(3, plan.rolls_required);
+        assert!((plan.leftover_on_last_roll.value - 70.0).abs() < EPSILON);
+        assert!((plan.offcut_waste.value - 20.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn piece_count_near_u32_max_should_not_overflow() {
+        let requirement = JobRequirement::Pieces { count: ::std::u32::MAX, cut_length: Length::new(30.0, CENTIMETERS) };
+        let plan = plan_job(&roll(), &requirement, &Length::zero()).unwrap();
+        assert_eq!(::std::u32::MAX / 3, plan.rolls_required);
+    }
+
+    #[test]
+    fn piece_longer_than_roll_should_return_none() {
+        let requirement = JobRequirement::Pieces { count: 1, cut_length: Length::new(150.0, CENTIMETERS) };
+        assert!(plan_job(&roll(), &requirement, &Length::zero()).is_none());
+    }
+}
@@ -0,0 +1,156 @@
+use super::MaterialRoll;
+use super::quantities::{Area, BasisWeight, Mass, KILOGRAMS};
+use super::units::{Length, LengthUnit, CENTIMETERS};
+
+use std::f64::consts::PI;
+
+/// One material in the stack that makes up each wrap, e.g. the product and its release liner.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Ply {
+    pub name: String,
+    pub thickness: Length,
+    pub basis_weight: Option<BasisWeight>,
+    pub is_product: bool
+}
+
+/// The plies wound together on every wrap of an interleaved or laminated roll, innermost first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlyStack {
+    pub plies: Vec<Ply>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlyReport {
+    pub name: String,
+    pub length: Length,
+    pub area: Area,
+    pub weight: Option<Mass>,
+    pub is_product: bool
+}
+
+/// Totals for a roll wound from a `PlyStack`. `total_weight` is `None` unless every ply has a
+/// basis weight.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LaminateSummary {
+    pub combined_thickness: Length,
+    pub product_length: Length,
+    pub total_weight: Option<Mass>,
+    pub plies: Vec<PlyReport>
+}
+
+impl PlyStack {
+
+    pub fn new(plies: Vec<Ply>) -> PlyStack {
+        PlyStack { plies: plies }
+    }
+
+    pub fn combined_thickness(&self) -> Length {
+        let unit: LengthUnit = CENTIMETERS;
+        let total = self.plies.iter().map(|ply| ply.thickness.value(&unit)).sum();
+        Length::new(total, unit)
+    }
+
+    /// Reports the length, area and weight of each ply wound between `id` and `od`. Each wrap
+    /// contains one layer of every ply, and a ply further out in the wrap sits on a larger
+    /// diameter, so it is longer than the plies inside it by `PI` times its offset per wrap.
+    pub fn summarize(&self, id: Length, od: Length, width: &Length) -> LaminateSummary {
+        let unit: LengthUnit = CENTIMETERS;
+        let roll = MaterialRoll::from_plies(id, od, self);
+        let inner_length = roll.get_roll_length().value(&unit);
+        let thickness = roll.thickness.value(&unit);
+        let wraps = if thickness > 0.0 {
+            ((roll.od.value(&unit) - roll.id.value(&unit)) / thickness).max(0.0)
+        } else {
+            0.0
+        };
+
+        let mut offset = 0.0;
+        let plies = self.plies.iter().map(|ply| {
+            let length = Length::new(inner_length + PI * offset * wraps, unit.clone());
+            let area: Area = length.clone() * width.clone();
+            offset += ply.thickness.value(&unit);
+            PlyReport {
+                name: ply.name.clone(),
+                length: length,
+                weight: ply.basis_weight.clone().map(|basis_weight| area.clone() * basis_weight),
+                area: area,
+                is_product: ply.is_product
+            }
+        }).collect::<Vec<PlyReport>>();
+
+        let total_weight = plies.iter().fold(Some(Mass::new(0.0, KILOGRAMS)), |total, ply| {
+            total.and_then(|total| ply.weight.clone().map(|weight| total + weight))
+        });
+        let product_length = plies.iter().filter(|ply| ply.is_product)
+            .fold(Length::new(0.0, unit), |total, ply| total + ply.length.clone());
+
+        LaminateSummary {
+            combined_thickness: roll.thickness,
+            product_length: product_length,
+            total_weight: total_weight,
+            plies: plies
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::MaterialRoll;
+    use ::estimator::quantities::{GRAMS_PER_SQUARE_METER, SQUARE_METERS};
+    use ::estimator::units::{Length, CENTIMETERS, METERS};
+
+    const EPSILON: f64 = 0.00001;
+
+    fn stack() -> PlyStack {
+        PlyStack::new(vec![
+            Ply { name: "Film".to_string(), thickness: Length::new(0.3, CENTIMETERS), basis_weight: Some(BasisWeight::new(50.0, GRAMS_PER_SQUARE_METER)), is_product: true },
+            Ply { name: "Liner".to_string(), thickness: Length::new(0.2, CENTIMETERS), basis_weight: Some(BasisWeight::new(30.0, GRAMS_PER_SQUARE_METER)), is_product: false }
+        ])
+    }
+
+    #[test]
+    fn combined_thickness_should_be_sum_of_plies() {
+        assert!((stack().combined_thickness().value - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn product_length_should_use_combined_thickness() {
+        let id = Length::new(10.0, CENTIMETERS);
+        let od = Length::new(20.0, CENTIMETERS);
+        let summary = stack().summarize(id.clone(), od.clone(), &Length::new(1.0, METERS));
+        let single_ply = MaterialRoll { id: id, od: od, thickness: Length::new(0.5, CENTIMETERS) };
+
+        assert!((summary.product_length.value(&METERS) - single_ply.get_roll_length().value(&METERS)).abs() < EPSILON);
+    }
+
+    #[test]
+    fn outer_plies_should_be_longer_by_their_offset_in_each_wrap() {
+        let summary = stack().summarize(Length::new(10.0, CENTIMETERS), Length::new(20.0, CENTIMETERS), &Length::new(1.0, METERS));
+        // 20 wraps, with the liner sitting 0.3 cm further out than the film on each
+        let extra = ::std::f64::consts::PI * 0.3 * 20.0;
+
+        assert!((summary.plies[1].length.value(&CENTIMETERS) - summary.plies[0].length.value(&CENTIMETERS) - extra).abs() < EPSILON);
+        assert!(summary.plies[1].area > summary.plies[0].area);
+    }
+
+    #[test]
+    fn weight_should_be_reported_per_ply_and_in_total() {
+        let summary = stack().summarize(Length::new(10.0, CENTIMETERS), Length::new(20.0, CENTIMETERS), &Length::new(1.0, METERS));
+        let film_area = summary.plies[0].area.value(&SQUARE_METERS);
+        let liner_area = summary.plies[1].area.value(&SQUARE_METERS);
+        let weight_kg = |weight: &Option<Mass>| weight.as_ref().unwrap().value(&KILOGRAMS);
+
+        assert!((weight_kg(&summary.plies[0].weight) - film_area * 0.05).abs() < EPSILON);
+        assert!((weight_kg(&summary.plies[1].weight) - liner_area * 0.03).abs() < EPSILON);
+        assert!((weight_kg(&summary.total_weight) - (film_area * 0.05 + liner_area * 0.03)).abs() < EPSILON);
+    }
+
+    #[test]
+    fn total_weight_should_be_none_if_a_ply_has_no_basis_weight() {
+        let mut plies = stack();
+        plies.plies[1].basis_weight = None;
+        let summary = plies.summarize(Length::new(10.0, CENTIMETERS), Length::new(20.0, CENTIMETERS), &Length::new(1.0, METERS));
+        assert!(summary.total_weight.is_none());
+    }
+}
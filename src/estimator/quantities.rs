@@ -0,0 +1,118 @@
+//! Physical quantities other than `Length`, and the products and quotients between them. Every
+//! quantity converts through a reference unit in SI (square meters, kilograms, seconds, etc.), so
+//! an expression like `length * width * basis_weight` yields a `Mass` regardless of the units each
+//! operand was entered in. `Length` lives in `units` but is built from the same macros.
+
+use std::ops::{Add, Sub, Mul, Div};
+use std::borrow::Cow;
+use std::cmp::*;
+
+use super::units::{Unit, Length, METERS};
+
+/// Access to the value of a quantity in its reference unit.
+pub trait Quantity {
+    fn reference_value(&self) -> f64;
+}
+
+unit!(AreaUnit);
+quantity!(Area, AreaUnit);
+unit!(VolumeUnit);
+quantity!(Volume, VolumeUnit);
+unit!(MassUnit);
+quantity!(Mass, MassUnit);
+unit!(DensityUnit);
+quantity!(Density, DensityUnit);
+unit!(BasisWeightUnit);
+quantity!(BasisWeight, BasisWeightUnit);
+unit!(TimeUnit);
+quantity!(Time, TimeUnit);
+unit!(SpeedUnit);
+quantity!(Speed, SpeedUnit);
+
+pub const SQUARE_METERS: AreaUnit = AreaUnit{ full_name: Cow::Borrowed("Square Meters"), abbrev: Cow::Borrowed("m²"), factor_to_reference: 1.0 };
+pub const SQUARE_CENTIMETERS: AreaUnit = AreaUnit{ full_name: Cow::Borrowed("Square Centimeters"), abbrev: Cow::Borrowed("cm²"), factor_to_reference: 0.0001 };
+pub const SQUARE_INCHES: AreaUnit = AreaUnit{ full_name: Cow::Borrowed("Square Inches"), abbrev: Cow::Borrowed("in²"), factor_to_reference: 0.00064516 };
+pub const SQUARE_YARDS: AreaUnit = AreaUnit{ full_name: Cow::Borrowed("Square Yards"), abbrev: Cow::Borrowed("yrd²"), factor_to_reference: 0.83612736 };
+
+pub const CUBIC_METERS: VolumeUnit = VolumeUnit{ full_name: Cow::Borrowed("Cubic Meters"), abbrev: Cow::Borrowed("m³"), factor_to_reference: 1.0 };
+pub const CUBIC_CENTIMETERS: VolumeUnit = VolumeUnit{ full_name: Cow::Borrowed("Cubic Centimeters"), abbrev: Cow::Borrowed("cm³"), factor_to_reference: 0.000001 };
+pub const LITERS: VolumeUnit = VolumeUnit{ full_name: Cow::Borrowed("Liters"), abbrev: Cow::Borrowed("L"), factor_to_reference: 0.001 };
+
+pub const KILOGRAMS: MassUnit = MassUnit{ full_name: Cow::Borrowed("Kilograms"), abbrev: Cow::Borrowed("kg"), factor_to_reference: 1.0 };
+pub const GRAMS: MassUnit = MassUnit{ full_name: Cow::Borrowed("Grams"), abbrev: Cow::Borrowed("g"), factor_to_reference: 0.001 };
+pub const POUNDS: MassUnit = MassUnit{ full_name: Cow::Borrowed("Pounds"), abbrev: Cow::Borrowed("lb"), factor_to_reference: 0.45359237 };
+
+pub const KILOGRAMS_PER_CUBIC_METER: DensityUnit = DensityUnit{ full_name: Cow::Borrowed("Kilograms per Cubic Meter"), abbrev: Cow::Borrowed("kg/m³"), factor_to_reference: 1.0 };
+pub const GRAMS_PER_CUBIC_CENTIMETER: DensityUnit = DensityUnit{ full_name: Cow::Borrowed("Grams per Cubic Centimeter"), abbrev: Cow::Borrowed("g/cm³"), factor_to_reference: 1000.0 };
+
+pub const KILOGRAMS_PER_SQUARE_METER: BasisWeightUnit = BasisWeightUnit{ full_name: Cow::Borrowed("Kilograms per Square Meter"), abbrev: Cow::Borrowed("kg/m²"), factor_to_reference: 1.0 };
+pub const GRAMS_PER_SQUARE_METER: BasisWeightUnit = BasisWeightUnit{ full_name: Cow::Borrowed("Grams per Square Meter"), abbrev: Cow::Borrowed("g/m²"), factor_to_reference: 0.001 };
+
+pub const SECONDS: TimeUnit = TimeUnit{ full_name: Cow::Borrowed("Seconds"), abbrev: Cow::Borrowed("s"), factor_to_reference: 1.0 };
+pub const MINUTES: TimeUnit = TimeUnit{ full_name: Cow::Borrowed("Minutes"), abbrev: Cow::Borrowed("min"), factor_to_reference: 60.0 };
+pub const HOURS: TimeUnit = TimeUnit{ full_name: Cow::Borrowed("Hours"), abbrev: Cow::Borrowed("h"), factor_to_reference: 3600.0 };
+
+pub const METERS_PER_SECOND: SpeedUnit = SpeedUnit{ full_name: Cow::Borrowed("Meters per Second"), abbrev: Cow::Borrowed("m/s"), factor_to_reference: 1.0 };
+pub const METERS_PER_MINUTE: SpeedUnit = SpeedUnit{ full_name: Cow::Borrowed("Meters per Minute"), abbrev: Cow::Borrowed("m/min"), factor_to_reference: 1.0 / 60.0 };
+pub const YARDS_PER_MINUTE: SpeedUnit = SpeedUnit{ full_name: Cow::Borrowed("Yards per Minute"), abbrev: Cow::Borrowed("yrd/min"), factor_to_reference: 0.9144 / 60.0 };
+
+product!(Length * Length = Area in SQUARE_METERS);
+product!(Area * Length = Volume in CUBIC_METERS);
+product!(Length * Area = Volume in CUBIC_METERS);
+product!(Volume * Density = Mass in KILOGRAMS);
+product!(Area * BasisWeight = Mass in KILOGRAMS);
+product!(Speed * Time = Length in METERS);
+
+quotient!(Area / Length = Length in METERS);
+quotient!(Volume / Area = Length in METERS);
+quotient!(Volume / Length = Area in SQUARE_METERS);
+quotient!(Mass / Volume = Density in KILOGRAMS_PER_CUBIC_METER);
+quotient!(Mass / Area = BasisWeight in KILOGRAMS_PER_SQUARE_METER);
+quotient!(Mass / Density = Volume in CUBIC_METERS);
+quotient!(Length / Time = Speed in METERS_PER_SECOND);
+quotient!(Length / Speed = Time in SECONDS);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::units::{Length, CENTIMETERS, INCHES, METERS, YARDS};
+
+    const EPSILON: f64 = 0.00001;
+
+    #[test]
+    fn length_times_width_should_be_an_area() {
+        let area: Area = Length::new(2.0, YARDS) * Length::new(36.0, INCHES);
+        assert_eq!(SQUARE_METERS, area.unit);
+        assert!((area.value(&SQUARE_YARDS) - 2.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn area_times_basis_weight_should_be_a_mass() {
+        let area = Length::new(100.0, METERS) * Length::new(150.0, CENTIMETERS);
+        let mass: Mass = area * BasisWeight::new(80.0, GRAMS_PER_SQUARE_METER);
+        assert!((mass.value(&KILOGRAMS) - 12.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn volume_times_density_should_be_a_mass() {
+        let volume: Volume = Area::new(2.0, SQUARE_METERS) * Length::new(10.0, CENTIMETERS);
+        let mass: Mass = volume * Density::new(0.9, GRAMS_PER_CUBIC_CENTIMETER);
+        assert!((mass.value(&KILOGRAMS) - 180.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn length_divided_by_speed_should_be_a_time() {
+        let time: Time = Length::new(120.0, METERS) / Speed::new(60.0, METERS_PER_MINUTE);
+        assert!((time.value(&MINUTES) - 2.0).abs() < EPSILON);
+
+        let speed: Speed = Length::new(120.0, METERS) / time;
+        assert!((speed.value(&METERS_PER_MINUTE) - 60.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn quantities_should_add_and_compare_across_units() {
+        let total = Mass::new(1.0, KILOGRAMS) + Mass::new(500.0, GRAMS);
+        assert!((total.value - 1.5).abs() < EPSILON);
+        assert!(Mass::new(1.0, POUNDS) < Mass::new(1.0, KILOGRAMS));
+    }
+}
@@ -0,0 +1,142 @@
+use super::MaterialRoll;
+use super::units::Length;
+
+/// Physical limits of the rewinder. Any limit left as `None` is not checked.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MachineLimits {
+    pub max_od: Option<Length>,
+    pub min_core: Option<Length>,
+    pub max_core: Option<Length>
+}
+
+/// How the material is rewound. `compression` scales the wound thickness, so `0.95` means the
+/// material winds 5% thinner on the new roll, e.g. from higher winding tension. When
+/// `thickness` is `None` the source roll's thickness is used.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RewindSpec {
+    pub core: Length,
+    pub thickness: Option<Length>,
+    pub compression: f64
+}
+
+impl RewindSpec {
+
+    pub fn new(core: Length) -> RewindSpec {
+        RewindSpec {
+            core: core,
+            thickness: None,
+            compression: 1.0
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum RewindWarning {
+    ExceedsMaxOd { od: Length, max_od: Length },
+    CoreTooSmall { core: Length, min_core: Length },
+    CoreTooLarge { core: Length, max_core: Length }
+}
+
+impl RewindWarning {
+
+    pub fn message(&self) -> String {
+        match *self {
+            RewindWarning::ExceedsMaxOd { ref od, ref max_od } =>
+                format!("Rewound OD of {} exceeds machine maximum of {}", od.format(), max_od.format()),
+            RewindWarning::CoreTooSmall { ref core, ref min_core } =>
+                format!("Core of {} is smaller than machine minimum of {}", core.format(), min_core.format()),
+            RewindWarning::CoreTooLarge { ref core, ref max_core } =>
+                format!("Core of {} is larger than machine maximum of {}", core.format(), max_core.format())
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RewindResult {
+    pub roll: MaterialRoll,
+    pub length: Length,
+    pub warnings: Vec<RewindWarning>
+}
+
+/// Transfers the full length of `source` onto the core described by `spec` and reports the
+/// resulting roll, along with any machine limits it violates. Returns `None` if the new core or
+/// wound thickness is not positive.
+pub fn rewind(source: &MaterialRoll, spec: &RewindSpec, limits: &MachineLimits) -> Option<RewindResult> {
+    let length = source.get_roll_length();
+    let thickness = spec.thickness.clone().unwrap_or_else(|| source.thickness.clone()) * spec.compression;
+
+    MaterialRoll::from_length(spec.core.clone(), thickness, &length).map(|roll| {
+        let mut warnings: Vec<RewindWarning> = Vec::new();
+
+        if let Some(ref max_od) = limits.max_od {
+            if roll.od > *max_od {
+                warnings.push(RewindWarning::ExceedsMaxOd { od: roll.od.convert_to(max_od.unit.clone()), max_od: max_od.clone() });
+            }
+        }
+        if let Some(ref min_core) = limits.min_core {
+            if spec.core < *min_core {
+                warnings.push(RewindWarning::CoreTooSmall { core: spec.core.clone(), min_core: min_core.clone() });
+            }
+        }
+        if let Some(ref max_core) = limits.max_core {
+            if spec.core > *max_core {
+                warnings.push(RewindWarning::CoreTooLarge { core: spec.core.clone(), max_core: max_core.clone() });
+            }
+        }
+
+        RewindResult {
+            roll: roll,
+            length: length,
+            warnings: warnings
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::MaterialRoll;
+    use ::estimator::units::{Length, INCHES};
+
+    fn source() -> MaterialRoll {
+        MaterialRoll {
+            id: Length::new(6.0, INCHES),
+            od: Length::new(20.0, INCHES),
+            thickness: Length::new(0.05, INCHES)
+        }
+    }
+
+    #[test]
+    fn rewinding_onto_smaller_core_should_give_smaller_od() {
+        let result = rewind(&source(), &RewindSpec::new(Length::new(3.0, INCHES)), &MachineLimits::default()).unwrap();
+        assert!(result.roll.od < source().od);
+        assert!(result.roll.od > Length::new(3.0, INCHES));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn compression_should_reduce_rewound_od() {
+        let core = Length::new(3.0, INCHES);
+        let uncompressed = rewind(&source(), &RewindSpec::new(core.clone()), &MachineLimits::default()).unwrap();
+        let spec = RewindSpec { compression: 0.9, ..RewindSpec::new(core) };
+        let compressed = rewind(&source(), &spec, &MachineLimits::default()).unwrap();
+        assert!(compressed.roll.od < uncompressed.roll.od);
+    }
+
+    #[test]
+    fn rewind_should_warn_when_machine_limits_are_exceeded() {
+        let limits = MachineLimits {
+            max_od: Some(Length::new(12.0, INCHES)),
+            min_core: Some(Length::new(6.0, INCHES)),
+            max_core: None
+        };
+        let result = rewind(&source(), &RewindSpec::new(Length::new(3.0, INCHES)), &limits).unwrap();
+
+        assert_eq!(2, result.warnings.len());
+        match result.warnings[0] {
+            RewindWarning::ExceedsMaxOd { .. } => {},
+            ref other => panic!("Expected max OD warning, got {:?}", other)
+        }
+        assert_eq!("Core of 3.00 in is smaller than machine minimum of 6.00 in", result.warnings[1].message());
+    }
+}
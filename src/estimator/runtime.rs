@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use super::MaterialRoll;
+use super::quantities::{Speed, Time, METERS_PER_SECOND, SECONDS};
+use super::units::Length;
+
+/// Time taken to run `length` through a line at `speed`, or `None` if the line is stopped.
+pub fn time_for(length: &Length, speed: &Speed) -> Option<Duration> {
+    if speed.value(&METERS_PER_SECOND) <= 0.0 || length.value < 0.0 {
+        return None;
+    }
+    let time: Time = length.clone() / speed.clone();
+    Some(duration_from_secs(time.value(&SECONDS)))
+}
+
+/// Time until the roll runs empty, starting from `current_od` or from a full roll when it is
+/// `None`.
+pub fn time_to_empty(roll: &MaterialRoll, current_od: Option<&Length>, speed: &Speed) -> Option<Duration> {
+    let remaining = current_od.map(|od| roll.get_length_at_diameter(od)).unwrap_or_else(|| roll.get_roll_length());
+    time_for(&remaining, speed)
+}
+
+/// Time until the roll unwinds down to `target_od`, e.g. the diameter at which the next splice
+/// should be prepared. Returns `None` if the roll is already at or below the target.
+pub fn time_to_diameter(roll: &MaterialRoll, current_od: Option<&Length>, target_od: &Length, speed: &Speed) -> Option<Duration> {
+    let remaining = current_od.map(|od| roll.get_length_at_diameter(od)).unwrap_or_else(|| roll.get_roll_length());
+    let at_target = roll.get_length_at_diameter(target_od);
+    if at_target.is_greater_than_or_equal(&remaining) {
+        return None;
+    }
+    time_for(&(remaining - at_target), speed)
+}
+
+/// Formats a duration as a countdown, e.g. `1:05:09`.
+pub fn format_countdown(duration: &Duration) -> String {
+    let total = duration.as_secs();
+    format!("{}:{:02}:{:02}", total / 3600, (total % 3600) / 60, total % 60)
+}
+
+fn duration_from_secs(secs: f64) -> Duration {
+    let whole = secs.floor();
+    Duration::new(whole as u64, ((secs - whole) * 1_000_000_000.0) as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+    use ::estimator::MaterialRoll;
+    use ::estimator::quantities::{Speed, METERS_PER_MINUTE};
+    use ::estimator::test::roll_holding;
+
+    // Two single layer wraps: 100 cm on the core and 110 cm on the second layer.
+    fn roll() -> MaterialRoll {
+        roll_holding(100.0, 2, 10.0 / ::std::f64::consts::PI)
+    }
+
+    #[test]
+    fn time_to_empty_should_divide_length_by_speed() {
+        let speed = Speed::new(0.21, METERS_PER_SECOND);
+        let duration = time_to_empty(&roll(), None, &speed).unwrap();
+        assert_eq!(10, duration.as_secs());
+    }
+
+    #[test]
+    fn time_to_diameter_should_only_count_length_above_target() {
+        let speed = Speed::new(1.1, METERS_PER_MINUTE);
+        let target = roll().id.clone() + roll().thickness.clone();
+        let duration = time_to_diameter(&roll(), None, &target, &speed).unwrap();
+        assert_eq!(60, duration.as_secs());
+    }
+
+    #[test]
+    fn stopped_line_should_not_have_a_run_time() {
+        let speed = Speed::new(0.0, METERS_PER_MINUTE);
+        assert!(time_to_empty(&roll(), None, &speed).is_none());
+    }
+
+    #[test]
+    fn countdown_should_pad_minutes_and_seconds() {
+        assert_eq!("1:05:09", format_countdown(&Duration::new(3909, 0)));
+    }
+}
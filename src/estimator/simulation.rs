@@ -0,0 +1,167 @@
+use std::f64::consts::PI;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use super::MaterialRoll;
+use super::quantities::{Area, BasisWeight, Mass, Speed, KILOGRAMS, METERS_PER_SECOND};
+use super::units::{Length, LengthUnit, Unit, METERS};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Unwind,
+    Wind
+}
+
+/// Inputs for `simulate`. `basis_weight` is used, along with `width`, to compute the mass and
+/// inertia of the wound material. The core is not included.
+#[derive(Debug, Clone)]
+pub struct SimulationSpec {
+    pub roll: MaterialRoll,
+    pub width: Length,
+    pub basis_weight: BasisWeight,
+    pub speed: Speed,
+    pub direction: Direction,
+    pub time_step: Duration
+}
+
+/// The state of the roll at one point in time. `inertia` is in kg·m² about the roll axis.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SimulationSample {
+    pub seconds: f64,
+    pub diameter: Length,
+    pub rpm: f64,
+    pub remaining: Length,
+    pub mass_kg: f64,
+    pub inertia: f64
+}
+
+pub const CSV_HEADER: &'static str = "time_s,diameter,rpm,remaining,mass_kg,inertia_kg_m2";
+
+/// Steps the roll through a full unwind (from OD down to the core) or wind (from the core up to
+/// OD) at constant line speed. The first sample is at time zero and the last is at the moment the
+/// roll is empty or full. Returns an empty series if the line is stopped or the step is zero.
+pub fn simulate(spec: &SimulationSpec) -> Vec<SimulationSample> {
+    let total_length = spec.roll.get_roll_length().value(&METERS);
+    let speed = spec.speed.value(&METERS_PER_SECOND);
+    let step = spec.time_step.as_secs() as f64 + spec.time_step.subsec_nanos() as f64 / 1_000_000_000.0;
+    if speed <= 0.0 || step <= 0.0 {
+        return Vec::new();
+    }
+
+    let end_time = total_length / speed;
+    let step_count = (end_time / step).ceil() as usize;
+
+    (0..step_count + 1).map(|idx| {
+        let seconds = (idx as f64 * step).min(end_time);
+        let run_length = speed * seconds;
+        let wound_length = match spec.direction {
+            Direction::Unwind => total_length - run_length,
+            Direction::Wind => run_length
+        };
+        sample_at(spec, seconds, wound_length.max(0.0), speed)
+    }).collect()
+}
+
+fn sample_at(spec: &SimulationSpec, seconds: f64, wound_length: f64, speed: f64) -> SimulationSample {
+    let wound = Length::new(wound_length, METERS);
+    let diameter = spec.roll.get_diameter_at_length(&wound).unwrap_or_else(|| spec.roll.od.clone());
+    let diameter_m = diameter.value(&METERS);
+    let core_m = spec.roll.id.value(&METERS);
+
+    let area: Area = wound.clone() * spec.width.clone();
+    let mass: Mass = area * spec.basis_weight.clone();
+    let mass_kg = mass.value(&KILOGRAMS);
+    let inertia = 0.5 * mass_kg * ((diameter_m / 2.0).powi(2) + (core_m / 2.0).powi(2));
+    let rpm = speed / (PI * diameter_m) * 60.0;
+
+    SimulationSample {
+        seconds: seconds,
+        diameter: diameter,
+        rpm: rpm,
+        remaining: wound,
+        mass_kg: mass_kg,
+        inertia: inertia
+    }
+}
+
+/// Writes the samples as CSV, with lengths expressed in `unit`.
+pub fn write_csv<W: Write>(samples: &[SimulationSample], unit: &LengthUnit, writer: &mut W) -> io::Result<()> {
+    try!(writeln!(writer, "{}", CSV_HEADER.replace("diameter", &format!("diameter_{}", unit.abbrev()))
+            .replace("remaining", &format!("remaining_{}", unit.abbrev()))));
+
+    for sample in samples {
+        try!(writeln!(writer, "{:.3},{:.4},{:.3},{:.4},{:.4},{:.6}",
+                sample.seconds,
+                sample.diameter.value(unit),
+                sample.rpm,
+                sample.remaining.value(unit),
+                sample.mass_kg,
+                sample.inertia));
+    }
+    Ok(())
+}
+
+pub fn to_csv(samples: &[SimulationSample], unit: &LengthUnit) -> String {
+    let mut buffer: Vec<u8> = Vec::new();
+    write_csv(samples, unit, &mut buffer).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+    use ::estimator::MaterialRoll;
+    use ::estimator::quantities::{BasisWeight, Speed, GRAMS_PER_SQUARE_METER, METERS_PER_SECOND};
+    use ::estimator::units::{Length, CENTIMETERS, METERS};
+
+    fn spec(direction: Direction) -> SimulationSpec {
+        SimulationSpec {
+            roll: MaterialRoll {
+                id: Length::new(10.0, CENTIMETERS),
+                od: Length::new(30.0, CENTIMETERS),
+                thickness: Length::new(0.5, CENTIMETERS)
+            },
+            width: Length::new(1.0, METERS),
+            basis_weight: BasisWeight::new(100.0, GRAMS_PER_SQUARE_METER),
+            speed: Speed::new(1.0, METERS_PER_SECOND),
+            direction: direction,
+            time_step: Duration::new(1, 0)
+        }
+    }
+
+    #[test]
+    fn unwinding_should_shrink_diameter_and_speed_up_rotation() {
+        let samples = simulate(&spec(Direction::Unwind));
+        let first = samples.first().unwrap();
+        let last = samples.last().unwrap();
+
+        assert!(first.diameter > last.diameter);
+        assert!(first.rpm < last.rpm);
+        assert!(first.mass_kg > last.mass_kg);
+        assert!(last.remaining.value.abs() < 0.0001);
+        assert!(last.mass_kg.abs() < 0.0001);
+    }
+
+    #[test]
+    fn winding_should_end_with_full_roll() {
+        let samples = simulate(&spec(Direction::Wind));
+        let total = spec(Direction::Wind).roll.get_roll_length().value(&METERS);
+        let last = samples.last().unwrap();
+
+        assert!(samples[0].remaining.value.abs() < 0.0001);
+        assert!((last.remaining.value - total).abs() < 0.0001);
+        assert!((last.mass_kg - total * 0.1).abs() < 0.0001);
+    }
+
+    #[test]
+    fn csv_should_have_header_and_one_line_per_sample() {
+        let samples = simulate(&spec(Direction::Unwind));
+        let csv = to_csv(&samples, &CENTIMETERS);
+        let lines = csv.lines().collect::<Vec<&str>>();
+
+        assert_eq!("time_s,diameter_cm,rpm,remaining_cm,mass_kg,inertia_kg_m2", lines[0]);
+        assert_eq!(samples.len() + 1, lines.len());
+        assert!(lines[1].starts_with("0.000,30.0000,"));
+    }
+}
@@ -0,0 +1,132 @@
+use super::MaterialRoll;
+use super::units::{Length, LengthUnit, CENTIMETERS};
+
+/// A master roll to be slit into narrower child rolls, which are rewound onto `child_core`. When
+/// `target_od` is given each child is wound up to that diameter, and the master yields as many
+/// sets of children as its length allows; otherwise the whole master becomes one set.
+#[derive(Debug, Clone)]
+pub struct SlittingSpec {
+    pub master: MaterialRoll,
+    pub master_width: Length,
+    pub slit_widths: Vec<Length>,
+    pub trim: Length,
+    pub child_core: Length,
+    pub target_od: Option<Length>
+}
+
+/// Results of `plan_slitting`. Lengths are in centimeters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SlittingPlan {
+    pub children_per_set: u32,
+    pub sets_per_master: u32,
+    pub child_rolls_total: u32,
+    pub child_length: Length,
+    pub child_od: Length,
+    pub leftover_length: Length,
+    pub leftover_width: Length
+}
+
+/// Plans how a master roll is slit. Returns `None` if the slits and trim are wider than the
+/// master, if there are no slits, or if the target OD is not larger than the child core.
+pub fn plan_slitting(spec: &SlittingSpec) -> Option<SlittingPlan> {
+    let unit: LengthUnit = CENTIMETERS;
+    let slit_total: f64 = spec.slit_widths.iter().map(|width| width.value(&unit)).sum();
+    let leftover_width = spec.master_width.value(&unit) - spec.trim.value(&unit) - slit_total;
+    if spec.slit_widths.is_empty() || leftover_width < 0.0 {
+        return None;
+    }
+
+    let master_length = spec.master.get_roll_length().value(&unit);
+    let child_length = match spec.target_od {
+        Some(ref target_od) => {
+            if *target_od <= spec.child_core {
+                return None;
+            }
+            let child = MaterialRoll {
+                id: spec.child_core.clone(),
+                od: target_od.clone(),
+                thickness: spec.master.thickness.clone()
+            };
+            child.get_roll_length().value(&unit).min(master_length)
+        }
+        None => master_length
+    };
+
+    if child_length <= 0.0 {
+        return None;
+    }
+
+    let child_length = Length::new(child_length, unit.clone());
+    let child = match MaterialRoll::from_length(spec.child_core.clone(), spec.master.thickness.clone(), &child_length) {
+        Some(child) => child,
+        None => return None
+    };
+    let sets_per_master = (master_length / child_length.value).floor() as u32;
+    let children_per_set = spec.slit_widths.len() as u32;
+
+    Some(SlittingPlan {
+        children_per_set: children_per_set,
+        sets_per_master: sets_per_master,
+        child_rolls_total: children_per_set * sets_per_master,
+        leftover_length: Length::new(master_length - sets_per_master as f64 * child_length.value, unit.clone()),
+        child_length: child_length,
+        child_od: child.od,
+        leftover_width: Length::new(leftover_width, unit)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::MaterialRoll;
+    use ::estimator::units::{Length, CENTIMETERS, INCHES};
+
+    const EPSILON: f64 = 0.0001;
+
+    fn spec(target_od: Option<f64>) -> SlittingSpec {
+        SlittingSpec {
+            master: MaterialRoll {
+                id: Length::new(10.0, CENTIMETERS),
+                od: Length::new(50.0, CENTIMETERS),
+                thickness: Length::new(0.1, CENTIMETERS)
+            },
+            master_width: Length::new(100.0, CENTIMETERS),
+            slit_widths: vec![Length::new(30.0, CENTIMETERS), Length::new(30.0, CENTIMETERS), Length::new(25.0, CENTIMETERS)],
+            trim: Length::new(5.0, CENTIMETERS),
+            child_core: Length::new(3.0, INCHES),
+            target_od: target_od.map(|od| Length::new(od, CENTIMETERS))
+        }
+    }
+
+    #[test]
+    fn slitting_without_target_od_should_rewind_whole_master() {
+        let plan = plan_slitting(&spec(None)).unwrap();
+        let master_length = spec(None).master.get_roll_length().value(&CENTIMETERS);
+
+        assert_eq!(3, plan.children_per_set);
+        assert_eq!(1, plan.sets_per_master);
+        assert_eq!(3, plan.child_rolls_total);
+        assert!((plan.child_length.value - master_length).abs() < EPSILON);
+        assert!(plan.leftover_length.value.abs() < EPSILON);
+        assert!((plan.leftover_width.value - 10.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn slitting_with_target_od_should_yield_multiple_sets() {
+        let plan = plan_slitting(&spec(Some(25.0))).unwrap();
+        let master_length = spec(None).master.get_roll_length().value(&CENTIMETERS);
+
+        assert!(plan.sets_per_master > 1);
+        assert_eq!(plan.sets_per_master * 3, plan.child_rolls_total);
+        assert!((plan.child_od.value(&CENTIMETERS) - 25.0).abs() < EPSILON);
+        let used = plan.child_length.value * plan.sets_per_master as f64;
+        assert!((master_length - used - plan.leftover_length.value).abs() < EPSILON);
+    }
+
+    #[test]
+    fn slits_wider_than_master_should_return_none() {
+        let mut too_wide = spec(None);
+        too_wide.slit_widths.push(Length::new(20.0, CENTIMETERS));
+        assert!(plan_slitting(&too_wide).is_none());
+    }
+}
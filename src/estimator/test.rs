@@ -1,6 +1,7 @@
 
 
 use super::*;
+use super::quantities::{BasisWeight, GRAMS_PER_SQUARE_METER, KILOGRAMS};
 
 use std::f64::consts::PI;
 
@@ -32,9 +33,169 @@ fn get_roll_length_should_return_length_of_multi_layer_roll() {
     assert_is_within(result.value(&INCHES), expected, EPSILON);
 }
 
+#[test]
+fn get_layer_count_should_return_number_of_wraps_between_id_and_od() {
+    let roll: MaterialRoll = MaterialRoll{
+        id: Length::new(4.0, INCHES),
+        od: Length::new(6.0, INCHES),
+        thickness: Length::new(1.0, INCHES)
+    };
+
+    assert_eq!(2, roll.get_layer_count());
+}
+
+#[test]
+fn get_layer_count_should_not_add_a_layer_for_floating_point_error() {
+    let roll: MaterialRoll = MaterialRoll{
+        id: Length::new(4.0, CENTIMETERS),
+        od: Length::new(12.0, CENTIMETERS),
+        thickness: Length::new(0.08, CENTIMETERS)
+    };
+    assert_eq!(100, roll.get_layer_count());
+
+    let roll: MaterialRoll = MaterialRoll{
+        id: Length::new(3.0, INCHES),
+        od: Length::new(3.3, INCHES),
+        thickness: Length::new(0.1, INCHES)
+    };
+    assert_eq!(3, roll.get_layer_count());
+}
+
+#[test]
+fn get_length_at_diameter_should_return_length_wound_up_to_that_diameter() {
+    let roll: MaterialRoll = MaterialRoll{
+        id: Length::new(4.0, INCHES),
+        od: Length::new(7.0, INCHES),
+        thickness: Length::new(1.0, INCHES)
+    };
+
+    let result = roll.get_length_at_diameter(&Length::new(6.0, INCHES));
+    let expected = (4.0 * PI) + (5.0 * PI);
+    assert_is_within(result.value(&INCHES), expected, EPSILON);
+
+    let result = roll.get_length_at_diameter(&Length::new(5.5, INCHES));
+    let expected = (4.0 * PI) + (0.5 * 5.0 * PI);
+    assert_is_within(result.value(&INCHES), expected, EPSILON);
+}
+
+#[test]
+fn get_diameter_at_length_should_invert_get_length_at_diameter() {
+    let roll: MaterialRoll = MaterialRoll{
+        id: Length::new(4.0, INCHES),
+        od: Length::new(12.0, INCHES),
+        thickness: Length::new(0.08, INCHES)
+    };
+
+    for &diameter in [4.0, 4.05, 7.3, 9.99, 11.96, 12.0].iter() {
+        let length = roll.get_length_at_diameter(&Length::new(diameter, INCHES));
+        let result = roll.get_diameter_at_length(&length).unwrap();
+        assert_is_within(result.value(&INCHES), diameter, EPSILON);
+    }
+}
+
+#[test]
+fn length_and_diameter_should_round_trip_at_every_layer_boundary() {
+    let roll: MaterialRoll = MaterialRoll{
+        id: Length::new(4.0, CENTIMETERS),
+        od: Length::new(12.0, CENTIMETERS),
+        thickness: Length::new(0.08, CENTIMETERS)
+    };
+
+    for layer in 0..101 {
+        let diameter = 4.0 + layer as f64 * 0.08;
+        let length = roll.get_length_at_diameter(&Length::new(diameter, CENTIMETERS));
+        assert_is_within(roll.get_diameter_at_length(&length).unwrap().value(&CENTIMETERS), diameter, EPSILON);
+
+        let rewound = MaterialRoll::from_length(roll.id.clone(), roll.thickness.clone(), &length).unwrap();
+        assert_is_within(rewound.od.value(&CENTIMETERS), diameter, EPSILON);
+        assert_eq!(layer, rewound.get_layer_count());
+    }
+}
+
+#[test]
+fn get_diameter_at_length_should_interpolate_within_a_layer() {
+    let roll: MaterialRoll = MaterialRoll{
+        id: Length::new(4.0, INCHES),
+        od: Length::new(6.0, INCHES),
+        thickness: Length::new(1.0, INCHES)
+    };
+
+    let length = Length::new((4.0 * PI) + (2.5 * PI), INCHES);
+    let result = roll.get_diameter_at_length(&length).unwrap();
+    assert_is_within(result.value(&INCHES), 5.5, EPSILON);
+}
+
+#[test]
+fn get_diameter_at_length_should_return_none_if_length_exceeds_roll() {
+    let roll: MaterialRoll = MaterialRoll{
+        id: Length::new(4.0, INCHES),
+        od: Length::new(6.0, INCHES),
+        thickness: Length::new(1.0, INCHES)
+    };
+
+    assert!(roll.get_diameter_at_length(&Length::new(10.0, METERS)).is_none());
+}
+
+#[test]
+fn get_length_curve_should_include_id_and_od() {
+    let roll: MaterialRoll = MaterialRoll{
+        id: Length::new(4.0, INCHES),
+        od: Length::new(12.0, INCHES),
+        thickness: Length::new(0.08, INCHES)
+    };
+
+    let curve = roll.get_length_curve(10);
+    assert_eq!(10, curve.len());
+    assert_is_within(curve[0].0.value(&INCHES), 4.0, EPSILON);
+    assert_is_within(curve[0].1.value(&INCHES), 0.0, EPSILON);
+    assert_is_within(curve[9].0.value(&INCHES), 12.0, EPSILON);
+    assert_is_within(curve[9].1.value(&INCHES), roll.get_roll_length().value(&INCHES), EPSILON);
+}
+
+#[test]
+fn from_length_should_solve_for_od_that_holds_the_length() {
+    let length = Length::new((4.0 * PI) + (2.5 * PI), INCHES);
+    let roll = MaterialRoll::from_length(Length::new(4.0, INCHES), Length::new(1.0, INCHES), &length).unwrap();
+
+    assert_is_within(roll.od.value(&INCHES), 5.5, EPSILON);
+    assert_is_within(roll.get_roll_length().value(&INCHES), length.value(&INCHES), EPSILON);
+}
+
+#[test]
+fn from_length_should_round_trip_through_get_roll_length() {
+    for &meters in [0.5, 12.0, 37.25, 250.0].iter() {
+        let length = Length::new(meters, METERS);
+        let roll = MaterialRoll::from_length(Length::new(3.0, INCHES), Length::new(0.35, MILLIMETERS), &length).unwrap();
+        assert_is_within(roll.get_roll_length().value(&METERS), meters, EPSILON);
+    }
+}
+
+#[test]
+fn get_weight_should_multiply_area_by_basis_weight() {
+    let roll = MaterialRoll::from_length(Length::new(4.0, INCHES), Length::new(0.1, MILLIMETERS), &Length::new(250.0, METERS)).unwrap();
+    let weight = roll.get_weight(Length::new(150.0, CENTIMETERS), BasisWeight::new(80.0, GRAMS_PER_SQUARE_METER));
+    assert_is_within(weight.value(&KILOGRAMS), 250.0 * 1.5 * 0.08, 0.0001);
+}
+
+#[test]
+fn from_length_should_return_none_for_zero_thickness() {
+    let length = Length::new(10.0, METERS);
+    assert!(MaterialRoll::from_length(Length::new(4.0, INCHES), Length::zero(), &length).is_none());
+}
 
 
 fn assert_is_within(actual: f64, expected: f64, epsilon: f64) {
     let diff: f64 = (actual - expected).abs();
     assert!(diff < epsilon, format!("Expected {} to be within {} of {}", actual, epsilon, expected))
 }
+
+/// A roll of `layers` layers, each `thickness` cm thick, wound on a core whose circumference is
+/// `core_length` cm, so the first layer holds exactly `core_length` cm.
+pub fn roll_holding(core_length: f64, layers: u32, thickness: f64) -> MaterialRoll {
+    let id = core_length / PI;
+    MaterialRoll {
+        id: Length::new(id, CENTIMETERS),
+        od: Length::new(id + thickness * layers as f64, CENTIMETERS),
+        thickness: Length::new(thickness, CENTIMETERS)
+    }
+}
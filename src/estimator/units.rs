@@ -1,151 +1,216 @@
 use std::ops::{Add, Sub, Mul, Div};
 use std::cmp::*;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use super::locale::{NumberLocale, ENGLISH};
+use super::quantities::Quantity;
 
 pub trait Unit: Sized {
-    fn abbrev(&self) -> &'static str;
+    fn abbrev(&self) -> &str;
     fn value_to_reference(&self, value: f64) -> f64;
     fn value_from_reference(&self, ref_value: f64) -> f64;
-    fn full_name(&self) -> &'static str;
+    fn full_name(&self) -> &str;
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub struct LengthUnit {
-    full_name: &'static str,
-    abbrev: &'static str,
-    factor_to_reference: f64
+unit!(LengthUnit);
+
+pub const METERS: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Meters"), abbrev: Cow::Borrowed("m"), factor_to_reference: 1.0f64 };
+pub const CENTIMETERS: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Centimeters"), abbrev: Cow::Borrowed("cm"), factor_to_reference: 0.01f64 };
+pub const MILLIMETERS: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Millimeters"), abbrev: Cow::Borrowed("mm"), factor_to_reference: 0.001f64 };
+pub const KILOMETERS: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Kilometers"), abbrev: Cow::Borrowed("km"), factor_to_reference: 1000.0f64 };
+
+pub const INCHES: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Inches"), abbrev: Cow::Borrowed("in"), factor_to_reference: 0.0254f64 };
+pub const FEET: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Feet"), abbrev: Cow::Borrowed("ft"), factor_to_reference: 0.3048f64 };
+pub const YARDS: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Yards"), abbrev: Cow::Borrowed("yrd"), factor_to_reference: 0.9144f64 };
+pub const MILES: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Miles"), abbrev: Cow::Borrowed("mi"), factor_to_reference: 1609.344f64 };
+
+/// Plastic film thickness. 100 gauge is one mil (0.001 in).
+pub const GAUGE: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Gauge"), abbrev: Cow::Borrowed("ga"), factor_to_reference: 0.000000254f64 };
+/// Paper and board thickness. One point is one mil (0.001 in).
+pub const CALIPER_POINTS: LengthUnit = LengthUnit{ full_name: Cow::Borrowed("Caliper Points"), abbrev: Cow::Borrowed("cal pt"), factor_to_reference: 0.0000254f64 };
+
+#[derive(Debug)]
+pub enum UnitConfigError {
+    Io(io::Error),
+    InvalidLine { line: usize, content: String },
+    UnknownReference { line: usize, abbrev: String },
+    DuplicateUnit { line: usize, abbrev: String }
 }
 
-impl Unit for LengthUnit {
-
-    fn abbrev(&self) -> &'static str {
-        self.abbrev
-    }
-
-    fn full_name(&self) -> &'static str {
-        self.full_name
-    }
-
-    fn value_to_reference(&self, value: f64) -> f64 {
-        return self.factor_to_reference * value;
+impl fmt::Display for UnitConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UnitConfigError::Io(ref err) => write!(f, "unable to read units file: {}", err),
+            UnitConfigError::InvalidLine { line, ref content } =>
+                write!(f, "line {}: expected `Name (abbrev) = factor unit`, got `{}`", line, content),
+            UnitConfigError::UnknownReference { line, ref abbrev } =>
+                write!(f, "line {}: unknown reference unit `{}`", line, abbrev),
+            UnitConfigError::DuplicateUnit { line, ref abbrev } =>
+                write!(f, "line {}: a unit with abbreviation `{}` already exists", line, abbrev)
+        }
     }
+}
 
-    fn value_from_reference(&self, value: f64) -> f64 {
-        return value / self.factor_to_reference;
+impl From<io::Error> for UnitConfigError {
+    fn from(err: io::Error) -> UnitConfigError {
+        UnitConfigError::Io(err)
     }
-
 }
 
-pub const METERS: LengthUnit = LengthUnit{ full_name: "Meters", abbrev: "m", factor_to_reference: 1.0f64 };
-pub const CENTIMETERS: LengthUnit = LengthUnit{ full_name: "Centimeters", abbrev: "cm", factor_to_reference: 0.01f64 };
-
-pub const INCHES: LengthUnit = LengthUnit{ full_name: "Inches", abbrev: "in", factor_to_reference: 0.0254f64 };
-pub const YARDS: LengthUnit = LengthUnit{ full_name: "Yards", abbrev: "yrd", factor_to_reference: 0.9144f64 };
-
+/// The set of length units known to the application. It starts with the built in units, and
+/// more can be registered at runtime, e.g. from a units file where each line reads:
+///
+/// ```text
+/// # comment
+/// Picas (pc) = 0.16667 in
+/// Ells (ell) = 45 in
+/// ```
+///
+/// The reference unit on the right must be built in or defined on an earlier line.
 #[derive(Debug, PartialEq, Clone)]
-pub struct Length {
-    pub value: f64,
-    pub unit: LengthUnit
+pub struct UnitRegistry {
+    units: Vec<LengthUnit>
 }
 
-impl Length {
-
-    pub fn new(value: f64, unit: LengthUnit) -> Length {
-        Length{value: value, unit: unit}
-    }
+impl UnitRegistry {
 
-    pub fn format(&self) -> String {
-        format!("{:.2} {}", self.value, self.unit.abbrev())
-    }
-
-    pub fn convert_to(&self, unit: LengthUnit) -> Length {
-        let converted_value: f64 = self.value(&unit);
-        return Length { value: converted_value, unit: unit };
-    }
-
-    pub fn value(&self, unit: &LengthUnit) -> f64 {
-        if self.unit == *unit {
-            self.value
-        } else {
-            let value_as_reference = self.unit.value_to_reference(self.value);
-            unit.value_from_reference(value_as_reference)
+    pub fn new() -> UnitRegistry {
+        UnitRegistry {
+            units: vec![METERS, CENTIMETERS, MILLIMETERS, KILOMETERS, INCHES, FEET, YARDS, MILES, GAUGE, CALIPER_POINTS]
         }
     }
 
-    pub fn is_greater_than(&self, other: &Length) -> bool {
-        self.value > other.value(&self.unit)
+    pub fn units(&self) -> &[LengthUnit] {
+        &self.units
     }
 
-    pub fn is_less_than(&self, other: &Length) -> bool {
-        self.value < other.value(&self.unit)
+    /// Units that were registered at runtime rather than built in.
+    pub fn custom_units(&self) -> &[LengthUnit] {
+        &self.units[UnitRegistry::new().units.len()..]
     }
 
-    pub fn is_greater_than_or_equal(&self, other: &Length) -> bool {
-        !self.is_less_than(other)
+    /// Looks up a unit by abbreviation, or by full name ignoring case. Abbreviations must match
+    /// exactly, since case tells units like `mm` and `Mm` apart.
+    pub fn find(&self, name: &str) -> Option<&LengthUnit> {
+        let name = name.trim();
+        self.units.iter().find(|unit| unit.abbrev() == name)
+            .or_else(|| self.units.iter().find(|unit| unit.full_name().eq_ignore_ascii_case(name)))
     }
 
-    pub fn is_less_than_or_equal(&self, other: &Length) -> bool {
-        !self.is_greater_than(other)
+    /// Adds a unit, returning `false` without adding it if its abbreviation is already taken.
+    pub fn register(&mut self, unit: LengthUnit) -> bool {
+        if self.find(unit.abbrev()).is_some() {
+            return false;
+        }
+        self.units.push(unit);
+        true
+    }
+
+    /// Registers every unit defined in `config`, returning the number added.
+    pub fn load_str(&mut self, config: &str) -> Result<usize, UnitConfigError> {
+        let mut count = 0;
+        for (idx, raw_line) in config.lines().enumerate() {
+            let line_number = idx + 1;
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let unit = try!(self.parse_definition(line, line_number));
+            let abbrev = unit.abbrev().to_string();
+            if !self.register(unit) {
+                return Err(UnitConfigError::DuplicateUnit { line: line_number, abbrev: abbrev });
+            }
+            count += 1;
+        }
+        Ok(count)
     }
 
-    pub fn zero() -> Length {
-        Length::new(0.0, CENTIMETERS)
+    pub fn load_file(&mut self, path: &Path) -> Result<usize, UnitConfigError> {
+        let mut config = String::new();
+        try!(try!(File::open(path)).read_to_string(&mut config));
+        self.load_str(&config)
     }
-}
-
-impl PartialOrd for Length {
 
-    fn partial_cmp(&self, other: &Length) -> Option<Ordering> {
-        let other_value: f64 = other.value(&self.unit);
-        self.value.partial_cmp(&other_value)
+    /// Parses a value with an optional unit suffix, such as `12.5 pc`. Values without a suffix
+    /// are in `default_unit`.
+    pub fn parse(&self, input: &str, default_unit: &LengthUnit) -> Option<Length> {
+        self.parse_localized(input, default_unit, &ENGLISH)
     }
 
-    fn lt(&self, other: &Length) -> bool {
-        self.is_less_than(other)
-    }
+    /// Like `parse`, with the number written in `locale`, such as `1.234,5 cm`.
+    pub fn parse_localized(&self, input: &str, default_unit: &LengthUnit, locale: &NumberLocale) -> Option<Length> {
+        let trimmed = input.trim();
+        let split_idx = trimmed.find(|ch: char| {
+            !(ch.is_digit(10) || ch == '-' || ch == '+' || ch == locale.decimal_separator ||
+                    ch == locale.thousands_separator || ch == '\u{a0}')
+        }).unwrap_or(trimmed.len());
+        let (number, suffix) = trimmed.split_at(split_idx);
 
-    fn le(&self, other: &Length) -> bool {
-        self.is_less_than_or_equal(other)
-    }
+        let unit_opt = if suffix.trim().is_empty() {
+            Some(default_unit)
+        } else {
+            self.find(suffix)
+        };
 
-    fn gt(&self, other: &Length) -> bool {
-        self.is_greater_than(other)
+        unit_opt.and_then(|unit| locale.parse_length(number, unit.clone()))
     }
 
-    fn ge(&self, other: &Length) -> bool {
-        self.is_greater_than_or_equal(other)
-    }
+    fn parse_definition(&self, line: &str, line_number: usize) -> Result<LengthUnit, UnitConfigError> {
+        let invalid = || UnitConfigError::InvalidLine { line: line_number, content: line.to_string() };
 
-}
+        let mut sides = line.splitn(2, '=');
+        let name_part = sides.next().unwrap_or("").trim();
+        let value_part = try!(sides.next().ok_or_else(&invalid)).trim();
 
-impl Add for Length {
-    type Output = Length;
+        let open = try!(name_part.rfind('(').ok_or_else(&invalid));
+        if !name_part.ends_with(')') {
+            return Err(invalid());
+        }
+        let full_name = name_part[..open].trim();
+        let abbrev = name_part[open + 1..name_part.len() - 1].trim();
+
+        let mut value_tokens = value_part.split_whitespace();
+        let factor = try!(value_tokens.next().and_then(|token| token.parse::<f64>().ok()).ok_or_else(&invalid));
+        let reference_abbrev = try!(value_tokens.next().ok_or_else(&invalid));
+        if full_name.is_empty() || abbrev.is_empty() || factor <= 0.0 || value_tokens.next().is_some() {
+            return Err(invalid());
+        }
+
+        let reference = try!(self.find(reference_abbrev).ok_or_else(|| {
+            UnitConfigError::UnknownReference { line: line_number, abbrev: reference_abbrev.to_string() }
+        }));
 
-    fn add(self, other: Length) -> Length {
-        Length::new(self.value + other.value(&self.unit), self.unit)
+        Ok(LengthUnit::new(full_name, abbrev, reference.value_to_reference(factor)))
     }
 }
 
-impl Sub for Length {
-    type Output = Length;
+quantity!(Length, LengthUnit);
 
-    fn sub(self, other: Length) -> Length {
-        Length::new(self.value - other.value(&self.unit), self.unit)
+impl Length {
+
+    pub fn is_greater_than(&self, other: &Length) -> bool {
+        self.value > other.value(&self.unit)
     }
-}
 
-impl Div<f64> for Length {
-    type Output = Length;
+    pub fn is_less_than(&self, other: &Length) -> bool {
+        self.value < other.value(&self.unit)
+    }
 
-    fn div(self, divisor: f64) -> Length {
-        Length::new(self.value / divisor, self.unit)
+    pub fn is_greater_than_or_equal(&self, other: &Length) -> bool {
+        !self.is_less_than(other)
     }
-}
 
-impl Mul<f64> for Length {
-    type Output = Length;
+    pub fn is_less_than_or_equal(&self, other: &Length) -> bool {
+        !self.is_greater_than(other)
+    }
 
-    fn mul(self, other: f64) -> Length {
-        Length::new(self.value * other, self.unit)
+    pub fn zero() -> Length {
+        Length::new(0.0, CENTIMETERS)
     }
 }
 
@@ -287,6 +352,92 @@ mod test {
         assert_equals(50.0f64, len.value, 0.0001f64);
     }
 
+    #[test]
+    fn registry_should_load_units_defined_in_terms_of_other_units() {
+        let mut registry = UnitRegistry::new();
+        let config = "# typesetting\nPicas (pc) = 0.16667 in\n\nPoints (pt) = 0.083333 pc\n";
+        assert_eq!(2, registry.load_str(config).unwrap());
+
+        let picas = registry.find("Picas").unwrap().clone();
+        assert_eq!("pc", picas.abbrev());
+        assert_equals(6.0, Length::new(1.0, INCHES).value(&picas), 0.001);
+        assert_eq!(2, registry.custom_units().len());
+    }
+
+    #[test]
+    fn registry_should_reject_invalid_and_duplicate_units() {
+        let mut registry = UnitRegistry::new();
+        match registry.load_str("Furlongs = 201 m") {
+            Err(UnitConfigError::InvalidLine { line: 1, .. }) => {},
+            other => panic!("Expected invalid line error, got {:?}", other)
+        }
+        match registry.load_str("Ells (ell) = 45 cubits") {
+            Err(UnitConfigError::UnknownReference { line: 1, .. }) => {},
+            other => panic!("Expected unknown reference error, got {:?}", other)
+        }
+        match registry.load_str("Metres (m) = 1 m") {
+            Err(UnitConfigError::DuplicateUnit { line: 1, .. }) => {},
+            other => panic!("Expected duplicate unit error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn registry_should_tell_abbreviations_apart_by_case() {
+        let mut registry = UnitRegistry::new();
+        assert_eq!(1, registry.load_str("Megameters (Mm) = 1000 km").unwrap());
+
+        assert_eq!(MILLIMETERS, *registry.find("mm").unwrap());
+        assert_eq!("Megameters", registry.find("Mm").unwrap().full_name());
+        assert_eq!("Megameters", registry.find("megameters").unwrap().full_name());
+        assert!(registry.find("MM").is_none());
+    }
+
+    #[test]
+    fn registry_parse_should_use_unit_suffix_or_default() {
+        let mut registry = UnitRegistry::new();
+        registry.load_str("Ells (ell) = 45 in").unwrap();
+
+        let ells = registry.parse("2 ell", &CENTIMETERS).unwrap();
+        assert_equals(90.0, ells.value(&INCHES), EPSILON);
+
+        let plain = registry.parse("2", &CENTIMETERS).unwrap();
+        assert_eq!(CENTIMETERS, plain.unit);
+        assert!(registry.parse("2 cubits", &CENTIMETERS).is_none());
+    }
+
+    #[test]
+    fn registry_parse_localized_should_accept_locale_separators() {
+        let registry = UnitRegistry::new();
+        let length = registry.parse_localized("1.234,5 cm", &INCHES, &::estimator::locale::GERMAN).unwrap();
+        assert_eq!(Length::new(1234.5, CENTIMETERS), length);
+
+        let spaced = registry.parse_localized("1 234,5 in", &CENTIMETERS, &::estimator::locale::FRENCH).unwrap();
+        assert_eq!(Length::new(1234.5, INCHES), spaced);
+    }
+
+    #[test]
+    fn one_hundred_gauge_should_be_one_thousandth_of_an_inch() {
+        let film = Length::new(100.0, GAUGE);
+        assert_equals(0.001, film.value(&INCHES), EPSILON);
+        assert_equals(0.00254, film.value(&CENTIMETERS), EPSILON);
+    }
+
+    #[test]
+    fn caliper_points_should_be_thousandths_of_an_inch() {
+        let board = Length::new(14.0, CALIPER_POINTS);
+        assert_equals(0.014, board.value(&INCHES), EPSILON);
+        assert_equals(1400.0, board.value(&GAUGE), 0.001);
+    }
+
+    #[test]
+    fn registry_parse_should_accept_thickness_units() {
+        let registry = UnitRegistry::new();
+        let film = registry.parse("80ga", &CENTIMETERS).unwrap();
+        assert_eq!(GAUGE, film.unit);
+        let board = registry.parse("16 cal pt", &CENTIMETERS).unwrap();
+        assert_eq!(CALIPER_POINTS, board.unit);
+    }
+
     #[test]
     fn test_unit_conversion() {
         let len_inches = Length{value: 66.11f64, unit: INCHES};
@@ -0,0 +1,99 @@
+//! Puts text on the system clipboard through the platform's command line tool, since the window
+//! library has no clipboard support.
+
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+#[derive(Debug)]
+pub enum ClipboardError {
+    NoTool,
+    Io(io::Error),
+    Failed { program: String }
+}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ClipboardError::NoTool => write!(f, "no clipboard tool found"),
+            ClipboardError::Io(ref err) => write!(f, "unable to run clipboard tool: {}", err),
+            ClipboardError::Failed { ref program } => write!(f, "{} failed", program)
+        }
+    }
+}
+
+impl From<io::Error> for ClipboardError {
+    fn from(err: io::Error) -> ClipboardError {
+        ClipboardError::Io(err)
+    }
+}
+
+/// The programs, with their arguments, that read text to copy from standard input, in the
+/// order to try them.
+pub fn clipboard_commands(os: &str, wayland: bool) -> Vec<(&'static str, Vec<&'static str>)> {
+    match os {
+        "macos" => vec![("pbcopy", vec![])],
+        "windows" => vec![("clip", vec![])],
+        _ if wayland => vec![("wl-copy", vec![]), ("xclip", vec!["-selection", "clipboard"])],
+        _ => vec![("xclip", vec!["-selection", "clipboard"]), ("xsel", vec!["--clipboard", "--input"])]
+    }
+}
+
+/// The bytes to send to `program`. Windows' `clip` reads standard input in the console's code
+/// page, which garbles anything outside ASCII, but recognizes UTF-16 marked with a byte order
+/// mark. The other tools all take UTF-8.
+pub fn encode_for(program: &str, text: &str) -> Vec<u8> {
+    if program == "clip" {
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in text.encode_utf16() {
+            bytes.push((unit & 0xFF) as u8);
+            bytes.push((unit >> 8) as u8);
+        }
+        bytes
+    } else {
+        text.as_bytes().to_vec()
+    }
+}
+
+/// Copies `text` with the first clipboard tool that is installed.
+pub fn copy(text: &str) -> Result<(), ClipboardError> {
+    let wayland = env::var("WAYLAND_DISPLAY").is_ok();
+    for &(program, ref args) in clipboard_commands(env::consts::OS, wayland).iter() {
+        let mut child = match Command::new(program).args(args).stdin(Stdio::piped()).spawn() {
+            Ok(child) => child,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(ClipboardError::Io(err))
+        };
+
+        // Standard input is closed when dropped, which tells the tool the text is complete
+        if let Some(mut stdin) = child.stdin.take() {
+            try!(stdin.write_all(&encode_for(program, text)));
+        }
+        return if try!(child.wait()).success() {
+            Ok(())
+        } else {
+            Err(ClipboardError::Failed { program: program.to_string() })
+        };
+    }
+    Err(ClipboardError::NoTool)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clipboard_tool_should_match_platform() {
+        assert_eq!(vec![("pbcopy", vec![])], clipboard_commands("macos", false));
+        assert_eq!("clip", clipboard_commands("windows", false)[0].0);
+        assert_eq!("wl-copy", clipboard_commands("linux", true)[0].0);
+        assert_eq!("xclip", clipboard_commands("linux", false)[0].0);
+    }
+
+    #[test]
+    fn text_for_clip_should_be_utf16_with_byte_order_mark() {
+        assert_eq!(vec![0xFF, 0xFE, 0x31, 0x00, 0xB5, 0x00, 0x6D, 0x00], encode_for("clip", "1µm"));
+        assert_eq!("1µm".as_bytes().to_vec(), encode_for("xclip", "1µm"));
+    }
+}
@@ -0,0 +1,213 @@
+use ::estimator::comparison::{RollMetrics, SortColumn, SORT_COLUMNS};
+use ::estimator::locale::NumberLocale;
+use ::estimator::units::{LengthUnit, Unit};
+use ::i18n::Translator;
+
+use super::layout::Layout;
+use super::state::{InputState, RollRowInput};
+
+use super::conrod::{
+    Button,
+    CharacterCache,
+    DropDownList,
+    Labelable,
+    Label,
+    Positionable,
+    Sizeable,
+    TextBox,
+    Ui,
+    Widget,
+    WidgetId
+};
+
+use super::{
+    TABLE_UNIT_LABEL,
+    TABLE_UNIT_DROP_DOWN,
+    TABLE_SORT_BUTTONS,
+    TABLE_ADD_ROW_BUTTON,
+    TABLE_NAME_FIELDS,
+    TABLE_THICKNESS_FIELDS,
+    TABLE_OD_FIELDS,
+    TABLE_ID_FIELDS,
+    TABLE_WIDTH_FIELDS,
+    TABLE_PRICE_FIELDS,
+    TABLE_LENGTH_CELLS,
+    TABLE_AREA_CELLS,
+    TABLE_PRICE_PER_LENGTH_CELLS,
+    TABLE_PRICE_PER_AREA_CELLS
+};
+
+pub const MAX_TABLE_ROWS: usize = 8;
+
+/// Cell size in reference units. Cells narrow further when the window is too small for every
+/// column at that width.
+const ROW_HEIGHT: f64 = 30.0;
+const CELL_WIDTH: f64 = 90.0;
+const COLUMN_COUNT: usize = 10;
+
+pub fn column_message_key(column: SortColumn) -> &'static str {
+    match column {
+        SortColumn::Name => "table.name",
+        SortColumn::Thickness => "table.thickness",
+        SortColumn::Od => "table.od",
+        SortColumn::Id => "table.id",
+        SortColumn::Width => "table.width",
+        SortColumn::Length => "table.length",
+        SortColumn::Area => "table.area",
+        SortColumn::PricePerRoll => "table.price_per_roll",
+        SortColumn::PricePerLength => "table.price_per_length",
+        SortColumn::PricePerArea => "table.price_per_area"
+    }
+}
+
+/// Column index of each sortable column within the table.
+fn column_position(column: SortColumn) -> usize {
+    match column {
+        SortColumn::Name => 0,
+        SortColumn::Thickness => 1,
+        SortColumn::Od => 2,
+        SortColumn::Id => 3,
+        SortColumn::Width => 4,
+        SortColumn::PricePerRoll => 5,
+        SortColumn::Length => 6,
+        SortColumn::Area => 7,
+        SortColumn::PricePerLength => 8,
+        SortColumn::PricePerArea => 9
+    }
+}
+
+/// A column's header. The dimensions are entered in `input_unit`, so their headers show it.
+pub fn column_label(column: SortColumn, input_unit: &LengthUnit, translator: &Translator) -> String {
+    let name = translator.tr(column_message_key(column));
+    match column {
+        SortColumn::Thickness | SortColumn::Od | SortColumn::Id | SortColumn::Width =>
+            format!("{} ({})", name, input_unit.abbrev()),
+        _ => name
+    }
+}
+
+fn draw_cell_input<C>(ui: &mut Ui<C>, value: &mut String, x: f64, y: f64, size: [f64; 2], id: WidgetId)
+        where C: CharacterCache {
+    TextBox::new(value)
+        .w_h(size[0], size[1])
+        .xy(x, y)
+        .react(|_: &mut String| {})
+        .set(id, ui);
+}
+
+fn format_price(price: Option<f64>, locale: &NumberLocale) -> String {
+    price.map(|val| locale.format_number(val, 4)).unwrap_or_else(|| "--".to_string())
+}
+
+/// Draws the comparison table across the layout's body, below the unit that every row's
+/// dimensions are entered in. Clicking a sortable column header sorts by that column, and
+/// clicking it again reverses the order.
+pub fn draw_comparison_table<C>(ui: &mut Ui<C>, app_state: &mut InputState, layout: &Layout) where C: CharacterCache {
+    let translator = &app_state.translator;
+    let (left, top) = (layout.body.left, layout.body.top);
+    let cell_w = layout.scaled(CELL_WIDTH).min(layout.body.width / COLUMN_COUNT as f64);
+    let row_h = layout.scaled(ROW_HEIGHT);
+    let cell_size = [cell_w - layout.scaled(4.0), row_h - layout.scaled(4.0)];
+    let cell_x = |column: usize| left + cell_w * (column as f64 + 0.5);
+    let row_y = |row: usize| top - row_h * (row as f64 + 1.5);
+
+    Label::new(&translator.tr("table.input_unit"))
+        .xy(cell_x(0), top - row_h * 0.5)
+        .set(TABLE_UNIT_LABEL, ui);
+
+    let mut input_units = app_state.get_input_unit_strings();
+    DropDownList::new(&mut input_units, &mut app_state.selected_unit)
+        .w_h(cell_w * 2.0 - layout.scaled(4.0), cell_size[1])
+        .xy(cell_x(1) + cell_w / 2.0, top - row_h * 0.5)
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            *selected_idx = Some(new_idx);
+        })
+        .set(TABLE_UNIT_DROP_DOWN, ui);
+
+    let input_unit = app_state.get_input_unit();
+    let mut sort_by: Option<SortColumn> = None;
+    for (idx, column) in SORT_COLUMNS.iter().enumerate() {
+        let arrow = if app_state.sort_column == *column {
+            if app_state.sort_ascending { " ^" } else { " v" }
+        } else {
+            ""
+        };
+
+        Button::new()
+            .label(&format!("{}{}", column_label(*column, &input_unit, translator), arrow))
+            .w_h(cell_size[0], cell_size[1])
+            .xy(cell_x(column_position(*column)), row_y(0))
+            .react(|| sort_by = Some(*column))
+            .set(TABLE_SORT_BUTTONS + idx, ui);
+    }
+
+    let locale = app_state.get_locale();
+    let display_rows: Vec<(usize, Option<RollMetrics>)> = app_state.get_comparison_rows();
+    for (display_idx, &(row_idx, ref metrics)) in display_rows.iter().enumerate().take(MAX_TABLE_ROWS) {
+        let y = row_y(display_idx + 1);
+        let row: &mut RollRowInput = &mut app_state.comparison_rows[row_idx];
+
+        draw_cell_input(ui, &mut row.name, cell_x(0), y, cell_size, TABLE_NAME_FIELDS + row_idx);
+        draw_cell_input(ui, &mut row.thickness, cell_x(1), y, cell_size, TABLE_THICKNESS_FIELDS + row_idx);
+        draw_cell_input(ui, &mut row.od, cell_x(2), y, cell_size, TABLE_OD_FIELDS + row_idx);
+        draw_cell_input(ui, &mut row.id, cell_x(3), y, cell_size, TABLE_ID_FIELDS + row_idx);
+        draw_cell_input(ui, &mut row.width, cell_x(4), y, cell_size, TABLE_WIDTH_FIELDS + row_idx);
+        draw_cell_input(ui, &mut row.price_per_roll, cell_x(5), y, cell_size, TABLE_PRICE_FIELDS + row_idx);
+
+        let cells: [(String, WidgetId); 4] = match *metrics {
+            Some(ref metrics) => [
+                (locale.format_length(&metrics.length), TABLE_LENGTH_CELLS),
+                (locale.format_number(metrics.area, 2), TABLE_AREA_CELLS),
+                (format_price(metrics.price_per_length, &locale), TABLE_PRICE_PER_LENGTH_CELLS),
+                (format_price(metrics.price_per_area, &locale), TABLE_PRICE_PER_AREA_CELLS)
+            ],
+            None => [
+                (translator.tr("table.invalid"), TABLE_LENGTH_CELLS),
+                ("--".to_string(), TABLE_AREA_CELLS),
+                ("--".to_string(), TABLE_PRICE_PER_LENGTH_CELLS),
+                ("--".to_string(), TABLE_PRICE_PER_AREA_CELLS)
+            ]
+        };
+
+        for (offset, &(ref text, id_base)) in cells.iter().enumerate() {
+            Label::new(text)
+                .xy(cell_x(6 + offset), y)
+                .set(id_base + row_idx, ui);
+        }
+    }
+
+    let row_count = app_state.comparison_rows.len();
+    if row_count < MAX_TABLE_ROWS {
+        let mut add_row = false;
+        Button::new()
+            .label(&translator.tr("table.add_roll"))
+            .w_h(cell_w, cell_size[1])
+            .xy(cell_x(0), row_y(row_count + 1))
+            .react(|| add_row = true)
+            .set(TABLE_ADD_ROW_BUTTON, ui);
+
+        if add_row {
+            app_state.add_comparison_row();
+        }
+    }
+
+    if let Some(column) = sort_by {
+        app_state.sort_comparison_by(column);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::units::INCHES;
+
+    #[test]
+    fn every_column_should_have_a_header_in_its_own_position() {
+        let translator = Translator::new();
+        let mut positions = SORT_COLUMNS.iter().map(|column| column_position(*column)).collect::<Vec<usize>>();
+        positions.sort();
+        assert_eq!((0..SORT_COLUMNS.len()).collect::<Vec<usize>>(), positions);
+        assert_eq!("Thickness (in)", column_label(SortColumn::Thickness, &INCHES, &translator));
+        assert_eq!("Name", column_label(SortColumn::Name, &INCHES, &translator));
+    }
+}
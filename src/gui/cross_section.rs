@@ -0,0 +1,141 @@
+use ::estimator::MaterialRoll;
+use ::estimator::units::{Length, LengthUnit, CENTIMETERS};
+use ::i18n::Translator;
+use ::report::ring_layers;
+
+use super::layout::Layout;
+use super::theme::UiTheme;
+use super::to_color;
+
+use super::conrod::{
+    CharacterCache,
+    Circle,
+    Colorable,
+    Label,
+    Positionable,
+    Ui,
+    Widget
+};
+
+use super::{
+    CROSS_SECTION_MATERIAL,
+    CROSS_SECTION_CONSUMED,
+    CROSS_SECTION_REMAINING,
+    CROSS_SECTION_RINGS,
+    CROSS_SECTION_CORE,
+    CROSS_SECTION_LAYER_LABEL
+};
+
+/// Radii of the cross-section drawing, scaled so that the outside diameter of the roll
+/// fills `max_radius`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CrossSection {
+    pub core_radius: f64,
+    pub outer_radius: f64,
+    pub current_radius: Option<f64>,
+    pub ring_radii: Vec<f64>,
+    pub layer_count: u32
+}
+
+impl CrossSection {
+
+    pub fn new(roll: &MaterialRoll, current_od: Option<&Length>, max_radius: f64) -> CrossSection {
+        let unit: LengthUnit = CENTIMETERS;
+        let od = roll.od.value(&unit);
+        let id = roll.id.value(&unit);
+        let scale = max_radius / od;
+        let layer_count = roll.get_layer_count();
+
+        let core_radius = id * scale;
+        let layer_width = (max_radius - core_radius) / layer_count.max(1) as f64;
+
+        let ring_radii = ring_layers(layer_count).into_iter()
+            .map(|layer| core_radius + layer as f64 * layer_width)
+            .collect::<Vec<f64>>();
+
+        CrossSection {
+            core_radius: core_radius,
+            outer_radius: max_radius,
+            current_radius: current_od.map(|current| current.value(&unit) * scale),
+            ring_radii: ring_radii,
+            layer_count: layer_count
+        }
+    }
+}
+
+pub fn draw_cross_section<C>(ui: &mut Ui<C>, cross_section: &CrossSection, x: f64, y: f64, layout: &Layout,
+        translator: &Translator, theme: &UiTheme) where C: CharacterCache {
+    Circle::fill(cross_section.outer_radius)
+        .xy(x, y)
+        .color(to_color(theme.roll_material))
+        .set(CROSS_SECTION_MATERIAL, ui);
+
+    if let Some(current_radius) = cross_section.current_radius {
+        Circle::fill(cross_section.outer_radius)
+            .xy(x, y)
+            .color(to_color(theme.roll_consumed))
+            .set(CROSS_SECTION_CONSUMED, ui);
+
+        Circle::fill(current_radius)
+            .xy(x, y)
+            .color(to_color(theme.roll_material))
+            .set(CROSS_SECTION_REMAINING, ui);
+    }
+
+    for (idx, radius) in cross_section.ring_radii.iter().enumerate() {
+        let is_consumed = cross_section.current_radius.map(|current| *radius > current).unwrap_or(false);
+        let ring_color = if is_consumed { theme.roll_consumed_ring } else { theme.roll_ring };
+
+        Circle::outline(*radius)
+            .xy(x, y)
+            .color(to_color(ring_color))
+            .set(CROSS_SECTION_RINGS + idx, ui);
+    }
+
+    Circle::fill(cross_section.core_radius)
+        .xy(x, y)
+        .color(to_color(theme.roll_core))
+        .set(CROSS_SECTION_CORE, ui);
+
+    Label::new(&translator.tr_args("label.layers", &[("count", &cross_section.layer_count.to_string())]))
+        .xy(x, y - cross_section.outer_radius - layout.scaled(15.0))
+        .set(CROSS_SECTION_LAYER_LABEL, ui);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::MaterialRoll;
+    use ::estimator::units::{Length, CENTIMETERS};
+    use ::report::MAX_RINGS;
+
+    fn roll(layers: f64) -> MaterialRoll {
+        MaterialRoll {
+            id: Length::new(4.0, CENTIMETERS),
+            od: Length::new(4.0 + layers, CENTIMETERS),
+            thickness: Length::new(1.0, CENTIMETERS)
+        }
+    }
+
+    #[test]
+    fn cross_section_should_scale_core_relative_to_outside_diameter() {
+        let section = CrossSection::new(&roll(4.0), None, 100.0);
+        assert_eq!(100.0, section.outer_radius);
+        assert!((section.core_radius - 50.0).abs() < 0.0001);
+        assert_eq!(4, section.layer_count);
+        assert_eq!(3, section.ring_radii.len());
+    }
+
+    #[test]
+    fn cross_section_should_limit_number_of_rings_drawn() {
+        let section = CrossSection::new(&roll(200.0), None, 100.0);
+        assert!(section.ring_radii.len() <= MAX_RINGS);
+    }
+
+    #[test]
+    fn cross_section_should_scale_current_diameter() {
+        let current = Length::new(6.0, CENTIMETERS);
+        let section = CrossSection::new(&roll(4.0), Some(&current), 100.0);
+        assert!((section.current_radius.unwrap() - 75.0).abs() < 0.0001);
+    }
+}
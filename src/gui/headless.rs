@@ -0,0 +1,144 @@
+//! Drives the interface without a window. Input is fed in as piston events and frames are
+//! drawn into a `Canvas` by the software rasterizer. Text is drawn as one solid block per
+//! character, so layouts can be checked without depending on font rendering.
+
+use super::Gui;
+use super::raster::Canvas;
+
+use super::conrod::{Theme, Ui};
+use super::graphics::{Context, DrawState, Graphics, ImageSize};
+use super::graphics::character::{Character, CharacterCache, FontSize};
+use super::input::{Button, Event, Input, Key, Motion, MouseButton, RenderArgs};
+
+pub struct BlankTexture;
+
+impl ImageSize for BlankTexture {
+    fn get_size(&self) -> (u32, u32) {
+        (1, 1)
+    }
+}
+
+static BLANK_TEXTURE: BlankTexture = BlankTexture;
+
+/// A character cache whose glyphs are all the same size, proportional to the font size.
+pub struct BlockGlyphs;
+
+impl CharacterCache for BlockGlyphs {
+    type Texture = BlankTexture;
+
+    fn character<'a>(&'a mut self, font_size: FontSize, ch: char) -> Character<'a, BlankTexture> {
+        let size = font_size as f64;
+        let height = if ch.is_whitespace() { 0.0 } else { size * 0.7 };
+        Character {
+            offset: [0.0, size * 0.7],
+            size: [size * 0.6, height],
+            texture: &BLANK_TEXTURE
+        }
+    }
+}
+
+/// Converts each triangle from normalized device coordinates to pixels and fills it.
+fn fill_triangles(canvas: &mut Canvas, vertices: &[f32], color: [f32; 4]) {
+    let (width, height) = (canvas.width as f64, canvas.height as f64);
+    for triangle in vertices.chunks(6).filter(|triangle| triangle.len() == 6) {
+        let corner = |idx: usize| {
+            [(triangle[2 * idx] as f64 + 1.0) / 2.0 * width, (1.0 - triangle[2 * idx + 1] as f64) / 2.0 * height]
+        };
+        canvas.fill_triangle([corner(0), corner(1), corner(2)], color);
+    }
+}
+
+impl Graphics for Canvas {
+    type Texture = BlankTexture;
+
+    fn clear_color(&mut self, color: [f32; 4]) {
+        self.clear(color);
+    }
+
+    fn clear_stencil(&mut self, _value: u8) {}
+
+    fn tri_list<F>(&mut self, _draw_state: &DrawState, color: &[f32; 4], mut f: F)
+            where F: FnMut(&mut FnMut(&[f32])) {
+        f(&mut |vertices: &[f32]| fill_triangles(self, vertices, *color));
+    }
+
+    /// Textures are only used for glyphs, which are drawn as solid blocks.
+    fn tri_list_uv<F>(&mut self, _draw_state: &DrawState, color: &[f32; 4], _texture: &BlankTexture, mut f: F)
+            where F: FnMut(&mut FnMut(&[f32], &[f32])) {
+        f(&mut |vertices: &[f32], _uvs: &[f32]| fill_triangles(self, vertices, *color));
+    }
+}
+
+/// The interface in a window of a fixed size that exists only in memory.
+pub struct HeadlessGui {
+    pub gui: Gui,
+    pub ui: Ui<BlockGlyphs>,
+    width: u32,
+    height: u32
+}
+
+impl HeadlessGui {
+
+    pub fn new(gui: Gui, width: u32, height: u32) -> HeadlessGui {
+        let mut headless = HeadlessGui {
+            gui: gui,
+            ui: Ui::new(BlockGlyphs, Theme::default()),
+            width: width,
+            height: height
+        };
+        headless.send(Event::Input(Input::Resize(width, height)));
+        headless
+    }
+
+    pub fn send(&mut self, event: Event) {
+        self.gui.handle_event(&mut self.ui, &event);
+    }
+
+    /// Runs the interface for one frame, as a render event does in the window.
+    pub fn frame(&mut self) {
+        let args = RenderArgs {
+            ext_dt: 0.0,
+            width: self.width,
+            height: self.height,
+            draw_width: self.width,
+            draw_height: self.height
+        };
+        self.send(Event::Render(args));
+        self.gui.update(&mut self.ui);
+    }
+
+    /// Converts a position in conrod's coordinates, centered with y upwards, to window pixels.
+    pub fn to_window(&self, xy: [f64; 2]) -> [f64; 2] {
+        [xy[0] + self.width as f64 / 2.0, self.height as f64 / 2.0 - xy[1]]
+    }
+
+    /// Moves the mouse to `xy` in window pixels and clicks there.
+    pub fn click(&mut self, xy: [f64; 2]) {
+        self.send(Event::Input(Input::Move(Motion::MouseCursor(xy[0], xy[1]))));
+        self.frame();
+        self.send(Event::Input(Input::Press(Button::Mouse(MouseButton::Left))));
+        self.frame();
+        self.send(Event::Input(Input::Release(Button::Mouse(MouseButton::Left))));
+        self.frame();
+    }
+
+    pub fn press_key(&mut self, key: Key) {
+        self.send(Event::Input(Input::Press(Button::Keyboard(key))));
+        self.frame();
+        self.send(Event::Input(Input::Release(Button::Keyboard(key))));
+        self.frame();
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        self.send(Event::Input(Input::Text(text.to_string())));
+        self.frame();
+    }
+
+    /// Runs a frame and draws it.
+    pub fn render(&mut self) -> Canvas {
+        self.frame();
+        let mut canvas = Canvas::new(self.width, self.height);
+        self.ui.draw(Context::new_abs(self.width as f64, self.height as f64), &mut canvas);
+        canvas
+    }
+}
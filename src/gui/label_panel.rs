@@ -0,0 +1,188 @@
+use ::label;
+
+use super::layout::Layout;
+use super::state::InputState;
+
+use std::path::Path;
+
+use super::conrod::{
+    Button,
+    CharacterCache,
+    DropDownList,
+    Label,
+    Labelable,
+    Positionable,
+    Sizeable,
+    TextBox,
+    Ui,
+    Widget
+};
+
+use super::{
+    LABEL_ROLL_SUMMARY,
+    LABEL_MATERIAL_LABEL,
+    LABEL_MATERIAL_FIELD,
+    LABEL_ROLL_ID_LABEL,
+    LABEL_ROLL_ID_FIELD,
+    LABEL_TEMPLATE_LABEL,
+    LABEL_TEMPLATE_DROP_DOWN,
+    LABEL_TARGET_LABEL,
+    LABEL_TARGET_FIELD,
+    LABEL_PRINT_BUTTON,
+    LABEL_STATUS_DISPLAY,
+    REPORT_PATH_LABEL,
+    REPORT_PATH_FIELD,
+    REPORT_SAVE_BUTTON,
+    REPORT_STATUS_DISPLAY
+};
+
+/// Renders the label for the current roll and sends it to the entered target, leaving a
+/// message saying whether it worked.
+pub fn print_label(app_state: &mut InputState) {
+    let status = match (app_state.get_label_zpl(), app_state.get_print_target()) {
+        (None, _) => app_state.translator.tr("plan.no_roll"),
+        (_, None) => app_state.translator.tr("print.no_target"),
+        (Some(zpl), Some(target)) => match label::print(&zpl, &target) {
+            Ok(()) => app_state.translator.tr_args("print.sent", &[("target", &target.to_string())]),
+            Err(err) => app_state.translator.tr_args("print.failed", &[("error", &err.to_string())])
+        }
+    };
+    app_state.label_status = Some(status);
+}
+
+/// Writes the PDF report for the current roll to the entered path, leaving a message saying
+/// whether it worked.
+pub fn save_report(app_state: &mut InputState) {
+    let path = app_state.report_path_input_value.trim().to_string();
+    let status = match app_state.get_report() {
+        None => app_state.translator.tr("plan.no_roll"),
+        Some(_) if path.is_empty() => app_state.translator.tr("report.no_path"),
+        Some(report) => match report.save(Path::new(&path)) {
+            Ok(()) => app_state.translator.tr_args("report.saved", &[("path", &path)]),
+            Err(err) => app_state.translator.tr_args("report.failed", &[("error", &err.to_string())])
+        }
+    };
+    app_state.report_status = Some(status);
+}
+
+/// Draws the label printer and report export in the layout's body. Both use the roll entered in
+/// the single roll view.
+pub fn draw_label_panel<C>(ui: &mut Ui<C>, app_state: &mut InputState, layout: &Layout) where C: CharacterCache {
+    let (left, top) = (layout.body.left, layout.body.top);
+    let vertical_spacing = layout.scaled(40.0);
+    let horizontal_pad = layout.scaled(25.0);
+    let field_w = layout.scaled(260.0).min(layout.body.width / 2.0);
+    let field_h = layout.scaled(30.0);
+    let locale = app_state.get_locale();
+    let translator = &app_state.translator;
+
+    let roll_summary = app_state.get_material_roll().map(|roll| {
+        translator.tr_args("plan.roll_summary", &[
+            ("id", &locale.format_length(&roll.id)),
+            ("od", &locale.format_length(&roll.od)),
+            ("length", &app_state.format_output_length(&roll.get_roll_length()))
+        ])
+    }).unwrap_or_else(|| translator.tr("plan.no_roll"));
+
+    Label::new(&roll_summary)
+        .xy(left + layout.scaled(250.0).min(layout.body.width / 2.0), top)
+        .set(LABEL_ROLL_SUMMARY, ui);
+
+    Label::new(&translator.tr("print.material"))
+        .down_from(LABEL_ROLL_SUMMARY, vertical_spacing)
+        .x(left + layout.scaled(65.0))
+        .set(LABEL_MATERIAL_LABEL, ui);
+
+    TextBox::new(&mut app_state.label_material_input_value)
+        .w_h(field_w, field_h)
+        .react(|_: &mut String| {})
+        .right_from(LABEL_MATERIAL_LABEL, horizontal_pad)
+        .align_middle_y()
+        .set(LABEL_MATERIAL_FIELD, ui);
+
+    Label::new(&translator.tr("print.roll_id"))
+        .down_from(LABEL_MATERIAL_LABEL, vertical_spacing)
+        .set(LABEL_ROLL_ID_LABEL, ui);
+
+    TextBox::new(&mut app_state.label_roll_id_input_value)
+        .w_h(field_w, field_h)
+        .react(|_: &mut String| {})
+        .align_left_of(LABEL_MATERIAL_FIELD)
+        .align_middle_y_of(LABEL_ROLL_ID_LABEL)
+        .set(LABEL_ROLL_ID_FIELD, ui);
+
+    Label::new(&translator.tr("print.template"))
+        .down_from(LABEL_ROLL_ID_LABEL, vertical_spacing)
+        .set(LABEL_TEMPLATE_LABEL, ui);
+
+    let mut templates = app_state.get_label_template_strings();
+    DropDownList::new(&mut templates, &mut app_state.selected_label_template)
+        .w_h(field_w, field_h)
+        .align_left_of(LABEL_MATERIAL_FIELD)
+        .align_middle_y_of(LABEL_TEMPLATE_LABEL)
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            *selected_idx = Some(new_idx);
+        })
+        .set(LABEL_TEMPLATE_DROP_DOWN, ui);
+
+    Label::new(&translator.tr("print.target"))
+        .down_from(LABEL_TEMPLATE_LABEL, vertical_spacing)
+        .set(LABEL_TARGET_LABEL, ui);
+
+    TextBox::new(&mut app_state.label_target_input_value)
+        .w_h(field_w, field_h)
+        .react(|_: &mut String| {})
+        .align_left_of(LABEL_MATERIAL_FIELD)
+        .align_middle_y_of(LABEL_TARGET_LABEL)
+        .set(LABEL_TARGET_FIELD, ui);
+
+    let mut print_pressed = false;
+    Button::new()
+        .label(&translator.tr("print.button"))
+        .w_h(layout.scaled(130.0), field_h)
+        .down_from(LABEL_TARGET_FIELD, vertical_spacing)
+        .align_left_of(LABEL_MATERIAL_FIELD)
+        .react(|| print_pressed = true)
+        .set(LABEL_PRINT_BUTTON, ui);
+
+    Label::new(&translator.tr("report.path"))
+        .down_from(LABEL_TARGET_LABEL, vertical_spacing * 2.5)
+        .set(REPORT_PATH_LABEL, ui);
+
+    TextBox::new(&mut app_state.report_path_input_value)
+        .w_h(field_w, field_h)
+        .react(|_: &mut String| {})
+        .align_left_of(LABEL_MATERIAL_FIELD)
+        .align_middle_y_of(REPORT_PATH_LABEL)
+        .set(REPORT_PATH_FIELD, ui);
+
+    let mut save_pressed = false;
+    Button::new()
+        .label(&translator.tr("report.button"))
+        .w_h(layout.scaled(180.0), field_h)
+        .down_from(REPORT_PATH_FIELD, vertical_spacing)
+        .align_left_of(LABEL_MATERIAL_FIELD)
+        .react(|| save_pressed = true)
+        .set(REPORT_SAVE_BUTTON, ui);
+
+    if print_pressed {
+        print_label(app_state);
+    }
+    if save_pressed {
+        save_report(app_state);
+    }
+
+    if let Some(ref status) = app_state.label_status {
+        Label::new(status)
+            .right_from(LABEL_PRINT_BUTTON, horizontal_pad)
+            .align_middle_y_of(LABEL_PRINT_BUTTON)
+            .set(LABEL_STATUS_DISPLAY, ui);
+    }
+
+    if let Some(ref status) = app_state.report_status {
+        Label::new(status)
+            .right_from(REPORT_SAVE_BUTTON, horizontal_pad)
+            .align_middle_y_of(REPORT_SAVE_BUTTON)
+            .set(REPORT_STATUS_DISPLAY, ui);
+    }
+}
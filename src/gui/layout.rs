@@ -0,0 +1,289 @@
+//! Placement of the single roll view's panels for the current window size. Conrod positions
+//! widgets relative to the center of the window with y increasing upwards, so every rectangle
+//! here is in those coordinates.
+
+/// The window size the fixed layout was designed for. At this size, and at a scale factor of
+/// one, the layout matches the original hand placed positions.
+pub const REFERENCE_WIDTH: f64 = 1100.0;
+pub const REFERENCE_HEIGHT: f64 = 520.0;
+
+const MIN_FIT: f64 = 0.75;
+const MAX_FIT: f64 = 2.0;
+
+/// Widths, in reference units, at which the panels switch from three columns to two, and from
+/// two columns to one.
+const WIDE_MIN_WIDTH: f64 = 1000.0;
+const MEDIUM_MIN_WIDTH: f64 = 700.0;
+
+/// Widths, in reference units, of the header's controls in the order they are placed from the
+/// right edge: the four mode buttons, last mode first, then the locale, language and theme lists.
+pub const HEADER_CONTROL_WIDTHS: [f64; 7] = [130.0, 130.0, 130.0, 130.0, 190.0, 140.0, 150.0];
+
+const HEADER_ROW_HEIGHT: f64 = 50.0;
+const HEADER_CONTROL_HEIGHT: f64 = 30.0;
+const HEADER_GAP: f64 = 10.0;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Rect {
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64
+}
+
+impl Rect {
+
+    pub fn new(left: f64, top: f64, width: f64, height: f64) -> Rect {
+        Rect { left: left, top: top, width: width, height: height }
+    }
+
+    pub fn right(&self) -> f64 {
+        self.left + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.top - self.height
+    }
+
+    pub fn center(&self) -> [f64; 2] {
+        [self.left + self.width / 2.0, self.top - self.height / 2.0]
+    }
+
+    /// Shrinks the rectangle by `padding` on every side.
+    pub fn inset(&self, padding: f64) -> Rect {
+        Rect::new(self.left + padding, self.top - padding,
+                (self.width - 2.0 * padding).max(0.0), (self.height - 2.0 * padding).max(0.0))
+    }
+
+    /// Splits off the top `fraction` of the rectangle, returning the top and bottom parts.
+    pub fn split_vertical(&self, fraction: f64) -> (Rect, Rect) {
+        let top_height = self.height * fraction;
+        (Rect::new(self.left, self.top, self.width, top_height),
+            Rect::new(self.left, self.top - top_height, self.width, self.height - top_height))
+    }
+
+    /// Splits off the left `fraction` of the rectangle, returning the left and right parts.
+    pub fn split_horizontal(&self, fraction: f64) -> (Rect, Rect) {
+        let left_width = self.width * fraction;
+        (Rect::new(self.left, self.top, left_width, self.height),
+            Rect::new(self.left + left_width, self.top, self.width - left_width, self.height))
+    }
+
+    /// The rectangle as `[left, bottom, width, height]`, as used by `draw_length_chart`.
+    pub fn to_bounds(&self) -> [f64; 4] {
+        [self.left, self.bottom(), self.width, self.height]
+    }
+}
+
+/// How the input, result and visualization panels are arranged.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Arrangement {
+    /// Inputs beside the visualizations, with the results across the bottom.
+    Wide,
+    /// Inputs across the top, with the results beside the visualizations below them.
+    Medium,
+    /// Every panel stacked in a single column.
+    Narrow
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Layout {
+    pub scale: f64,
+    pub arrangement: Arrangement,
+    pub header: Rect,
+    /// Where each of `HEADER_CONTROL_WIDTHS` is drawn. Controls that don't fit beside the ones
+    /// already placed wrap onto another row, and the header grows to hold them.
+    pub header_controls: [Rect; 7],
+    /// Everything below the header, which the comparison, planner and label views fill.
+    pub body: Rect,
+    pub inputs: Rect,
+    pub results: Rect,
+    pub visuals: Rect
+}
+
+impl Layout {
+
+    /// Lays out a window of `win_w` by `win_h` conrod units. `scale_factor` is the display's
+    /// pixel density relative to a standard screen (2.0 on most 4K and tablet displays), and
+    /// everything is drawn that much larger so it stays readable. Within that, the layout grows
+    /// or shrinks with the window, within limits, before the panels reflow.
+    pub fn new(win_w: f64, win_h: f64, scale_factor: f64) -> Layout {
+        let density = scale_factor.max(1.0);
+        let fit = (win_w / (REFERENCE_WIDTH * density)).min(win_h / (REFERENCE_HEIGHT * density))
+            .max(MIN_FIT)
+            .min(MAX_FIT);
+        let scale = fit * density;
+        let width_in_reference_units = win_w / scale;
+
+        let arrangement = if width_in_reference_units >= WIDE_MIN_WIDTH {
+            Arrangement::Wide
+        } else if width_in_reference_units >= MEDIUM_MIN_WIDTH {
+            Arrangement::Medium
+        } else {
+            Arrangement::Narrow
+        };
+
+        let window = Rect::new(-win_w / 2.0, win_h / 2.0, win_w, win_h);
+        let (header_controls, header_rows) = place_header_controls(&window, scale);
+        let header_height = HEADER_ROW_HEIGHT * scale * header_rows as f64;
+        let (header, body) = window.split_vertical((header_height / win_h).min(1.0));
+        let body = body.inset(10.0 * scale);
+
+        let (inputs, results, visuals) = match arrangement {
+            Arrangement::Wide => {
+                let (top, results) = body.split_vertical(0.65);
+                let (inputs, visuals) = top.split_horizontal(0.5);
+                (inputs, results, visuals)
+            }
+            Arrangement::Medium => {
+                let (inputs, rest) = body.split_vertical(0.5);
+                let (results, visuals) = rest.split_horizontal(0.5);
+                (inputs, results, visuals)
+            }
+            Arrangement::Narrow => {
+                let (inputs, rest) = body.split_vertical(0.45);
+                let (results, visuals) = rest.split_vertical(0.4);
+                (inputs, results, visuals)
+            }
+        };
+
+        Layout {
+            scale: scale,
+            arrangement: arrangement,
+            header: header,
+            header_controls: header_controls,
+            body: body,
+            inputs: inputs,
+            results: results,
+            visuals: visuals
+        }
+    }
+
+    /// Scales a size given in reference units.
+    pub fn scaled(&self, value: f64) -> f64 {
+        value * self.scale
+    }
+
+    pub fn font_size(&self, base: u32) -> u32 {
+        ((base as f64 * self.scale).round() as u32).max(8)
+    }
+
+    /// Vertical distance between rows of the inputs panel, shrinking to fit `rows` rows.
+    pub fn row_spacing(&self, rows: usize) -> f64 {
+        self.scaled(40.0).min(self.inputs.height / rows.max(1) as f64)
+    }
+
+    /// Divides the results panel between the length and cost summary and the run time
+    /// countdown, side by side when there is room and stacked otherwise. Returns the summary's
+    /// rectangle first.
+    pub fn result_panels(&self) -> (Rect, Rect) {
+        if self.results.width >= self.scaled(800.0) {
+            self.results.split_horizontal(0.55)
+        } else {
+            self.results.split_vertical(0.5)
+        }
+    }
+
+    /// Divides the visualizations panel between the cross-section and the chart, side by side
+    /// when there is room and stacked otherwise. Returns the cross-section's rectangle first.
+    pub fn visual_panels(&self) -> (Rect, Rect) {
+        if self.visuals.width >= self.visuals.height {
+            self.visuals.split_horizontal(0.45)
+        } else {
+            self.visuals.split_vertical(0.5)
+        }
+    }
+}
+
+/// Places the header's controls from the right edge of `window` leftwards, starting a new row
+/// below whenever the next control would run past the left edge. Returns the controls'
+/// rectangles and the number of rows used.
+fn place_header_controls(window: &Rect, scale: f64) -> ([Rect; 7], usize) {
+    let gap = HEADER_GAP * scale;
+    let height = HEADER_CONTROL_HEIGHT * scale;
+    let mut controls = [Rect::new(0.0, 0.0, 0.0, 0.0); 7];
+    let mut row = 0;
+    let mut right = window.right() - gap;
+
+    for (idx, width) in HEADER_CONTROL_WIDTHS.iter().map(|width| width * scale).enumerate() {
+        if right - width < window.left + gap && right < window.right() - gap {
+            row += 1;
+            right = window.right() - gap;
+        }
+        let row_center = window.top - HEADER_ROW_HEIGHT * scale * (row as f64 + 0.5);
+        controls[idx] = Rect::new(right - width, row_center + height / 2.0, width, height);
+        right -= width + gap;
+    }
+
+    (controls, row + 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reference_window_should_use_three_columns_at_unit_scale() {
+        let layout = Layout::new(REFERENCE_WIDTH, REFERENCE_HEIGHT, 1.0);
+        assert_eq!(1.0, layout.scale);
+        assert_eq!(Arrangement::Wide, layout.arrangement);
+        assert!(layout.inputs.right() <= layout.visuals.left);
+        assert!(layout.results.top <= layout.inputs.bottom());
+        assert_eq!(layout.results.width, layout.inputs.width + layout.visuals.width);
+        assert_eq!(14, layout.font_size(14));
+    }
+
+    #[test]
+    fn high_density_display_should_scale_everything_up() {
+        let layout = Layout::new(2.0 * REFERENCE_WIDTH, 2.0 * REFERENCE_HEIGHT, 2.0);
+        assert_eq!(2.0, layout.scale);
+        assert_eq!(Arrangement::Wide, layout.arrangement);
+        assert_eq!(28, layout.font_size(14));
+    }
+
+    #[test]
+    fn narrow_windows_should_reflow_panels() {
+        let medium = Layout::new(700.0, 600.0, 1.0);
+        assert_eq!(Arrangement::Medium, medium.arrangement);
+        assert!(medium.results.top <= medium.inputs.bottom());
+        assert!(medium.visuals.left >= medium.results.right());
+
+        let narrow = Layout::new(500.0, 900.0, 1.0);
+        assert_eq!(Arrangement::Narrow, narrow.arrangement);
+        assert!(narrow.visuals.top <= narrow.results.bottom());
+        assert_eq!(narrow.inputs.left, narrow.visuals.left);
+    }
+
+    #[test]
+    fn header_controls_should_wrap_to_stay_inside_narrow_windows() {
+        let wide = Layout::new(REFERENCE_WIDTH, REFERENCE_HEIGHT, 1.0);
+        assert_eq!(50.0, wide.header.height);
+        assert!(wide.header_controls.iter().all(|control| control.center()[1] == wide.header.center()[1]));
+
+        let narrow = Layout::new(500.0, 900.0, 1.0);
+        assert_eq!(Arrangement::Narrow, narrow.arrangement);
+        assert!(narrow.header.height > 50.0);
+        assert!(narrow.inputs.top <= narrow.header.bottom());
+        for control in narrow.header_controls.iter() {
+            assert!(control.left >= narrow.header.left && control.right() <= narrow.header.right());
+            assert!(control.bottom() >= narrow.header.bottom());
+        }
+    }
+
+    #[test]
+    fn scale_should_be_limited_for_very_small_or_large_windows() {
+        assert_eq!(MIN_FIT, Layout::new(300.0, 200.0, 1.0).scale);
+        assert_eq!(MAX_FIT, Layout::new(5000.0, 3000.0, 1.0).scale);
+    }
+
+    #[test]
+    fn panels_should_stay_inside_the_window() {
+        for &(w, h, factor) in [(1100.0, 520.0, 1.0), (800.0, 600.0, 1.0), (500.0, 900.0, 1.5), (3840.0, 2160.0, 2.0)].iter() {
+            let layout = Layout::new(w, h, factor);
+            for panel in [layout.inputs, layout.results, layout.visuals].iter() {
+                assert!(panel.left >= -w / 2.0 && panel.right() <= w / 2.0 + 0.0001);
+                assert!(panel.top <= h / 2.0 && panel.bottom() >= -h / 2.0 - 0.0001);
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use ::estimator::MaterialRoll;
+use ::estimator::locale::NumberLocale;
+use ::estimator::units::{LengthUnit, Unit};
+use ::i18n::Translator;
+use ::report::CHART_SAMPLES;
+
+use super::layout::Layout;
+use super::theme::UiTheme;
+use super::to_color;
+
+use super::conrod::{
+    CharacterCache,
+    Colorable,
+    Label,
+    Line,
+    PointPath,
+    Positionable,
+    Ui,
+    Widget
+};
+
+use super::{
+    CHART_X_AXIS,
+    CHART_Y_AXIS,
+    CHART_CURVE,
+    CHART_X_LABEL,
+    CHART_Y_LABEL,
+    CHART_HOVER_MARKER,
+    CHART_READOUT
+};
+
+/// Sampled length-vs-diameter curve in display units. By default the x axis is the diameter and
+/// the y axis is the cumulative length; `swap_axes` plots the reverse.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LengthChart {
+    pub points: Vec<[f64; 2]>,
+    pub x_label: String,
+    pub y_label: String,
+    x_abbrev: String,
+    y_abbrev: String
+}
+
+impl LengthChart {
+
+    pub fn new(roll: &MaterialRoll, diameter_unit: LengthUnit, length_unit: LengthUnit, swap_axes: bool,
+            translator: &Translator) -> LengthChart {
+        let points = roll.get_length_curve(CHART_SAMPLES).iter().map(|&(ref diameter, ref length)| {
+            let diameter_val = diameter.value(&diameter_unit);
+            let length_val = length.value(&length_unit);
+            if swap_axes { [length_val, diameter_val] } else { [diameter_val, length_val] }
+        }).collect::<Vec<[f64; 2]>>();
+
+        let diameter_label = translator.tr_args("chart.diameter", &[("unit", diameter_unit.abbrev())]);
+        let length_label = translator.tr_args("chart.length", &[("unit", length_unit.abbrev())]);
+
+        if swap_axes {
+            LengthChart {
+                points: points,
+                x_label: length_label,
+                y_label: diameter_label,
+                x_abbrev: length_unit.abbrev().to_string(),
+                y_abbrev: diameter_unit.abbrev().to_string()
+            }
+        } else {
+            LengthChart {
+                points: points,
+                x_label: diameter_label,
+                y_label: length_label,
+                x_abbrev: diameter_unit.abbrev().to_string(),
+                y_abbrev: length_unit.abbrev().to_string()
+            }
+        }
+    }
+
+    pub fn x_range(&self) -> (f64, f64) {
+        range(self.points.iter().map(|p| p[0]))
+    }
+
+    pub fn y_range(&self) -> (f64, f64) {
+        range(self.points.iter().map(|p| p[1]))
+    }
+
+    /// Maps a point in display units onto the rectangle `[left, bottom, width, height]`.
+    pub fn to_screen(&self, point: [f64; 2], rect: [f64; 4]) -> [f64; 2] {
+        let (min_x, max_x) = self.x_range();
+        let (min_y, max_y) = self.y_range();
+        [
+            rect[0] + scale(point[0], min_x, max_x) * rect[2],
+            rect[1] + scale(point[1], min_y, max_y) * rect[3]
+        ]
+    }
+
+    /// Linearly interpolates the curve at the given x value, returning `None` outside the curve.
+    pub fn value_at(&self, x: f64) -> Option<[f64; 2]> {
+        self.points.windows(2).find(|pair| x >= pair[0][0] && x <= pair[1][0]).map(|pair| {
+            let span = pair[1][0] - pair[0][0];
+            let fraction = if span > 0.0 { (x - pair[0][0]) / span } else { 0.0 };
+            [x, pair[0][1] + (pair[1][1] - pair[0][1]) * fraction]
+        })
+    }
+
+    pub fn format_readout(&self, point: [f64; 2], locale: &NumberLocale) -> String {
+        format!("{} {} -> {} {}", locale.format_number(point[0], 2), self.x_abbrev,
+                locale.format_number(point[1], 2), self.y_abbrev)
+    }
+}
+
+fn range<I>(values: I) -> (f64, f64) where I: Iterator<Item=f64> {
+    values.fold((::std::f64::MAX, ::std::f64::MIN), |(min, max), val| {
+        (min.min(val), max.max(val))
+    })
+}
+
+fn scale(value: f64, min: f64, max: f64) -> f64 {
+    if max > min { (value - min) / (max - min) } else { 0.0 }
+}
+
+/// Draws the chart into the rectangle `[left, bottom, width, height]`, with a readout of the
+/// curve value under the mouse when it hovers over the plot area.
+pub fn draw_length_chart<C>(ui: &mut Ui<C>, chart: &LengthChart, rect: [f64; 4], layout: &Layout,
+        locale: &NumberLocale, theme: &UiTheme) where C: CharacterCache {
+    let origin = [rect[0], rect[1]];
+
+    Line::abs(origin, [rect[0] + rect[2], rect[1]])
+        .color(to_color(theme.chart_axis))
+        .set(CHART_X_AXIS, ui);
+
+    Line::abs(origin, [rect[0], rect[1] + rect[3]])
+        .color(to_color(theme.chart_axis))
+        .set(CHART_Y_AXIS, ui);
+
+    let screen_points = chart.points.iter()
+        .map(|point| chart.to_screen(*point, rect))
+        .collect::<Vec<[f64; 2]>>();
+
+    PointPath::abs(screen_points)
+        .color(to_color(theme.chart_curve))
+        .set(CHART_CURVE, ui);
+
+    Label::new(&chart.x_label)
+        .xy(rect[0] + rect[2] / 2.0, rect[1] - layout.scaled(15.0))
+        .set(CHART_X_LABEL, ui);
+
+    Label::new(&chart.y_label)
+        .xy(rect[0], rect[1] + rect[3] + layout.scaled(15.0))
+        .set(CHART_Y_LABEL, ui);
+
+    let mouse_xy = ui.mouse.xy;
+    let is_hovering = mouse_xy[0] >= rect[0] && mouse_xy[0] <= rect[0] + rect[2] &&
+            mouse_xy[1] >= rect[1] && mouse_xy[1] <= rect[1] + rect[3];
+
+    let hovered_value = if is_hovering {
+        let (min_x, max_x) = chart.x_range();
+        let x = min_x + (mouse_xy[0] - rect[0]) / rect[2] * (max_x - min_x);
+        chart.value_at(x)
+    } else {
+        None
+    };
+
+    if let Some(point) = hovered_value {
+        let screen_point = chart.to_screen(point, rect);
+        Line::abs([screen_point[0], rect[1]], [screen_point[0], rect[1] + rect[3]])
+            .color(to_color(theme.chart_marker))
+            .set(CHART_HOVER_MARKER, ui);
+
+        Label::new(&chart.format_readout(point, locale))
+            .xy(rect[0] + rect[2] / 2.0, rect[1] + rect[3] + layout.scaled(35.0))
+            .set(CHART_READOUT, ui);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::MaterialRoll;
+    use ::estimator::locale::{ENGLISH, GERMAN};
+    use ::estimator::units::{Length, INCHES, YARDS};
+    use ::i18n::Translator;
+
+    fn roll() -> MaterialRoll {
+        MaterialRoll {
+            id: Length::new(4.0, INCHES),
+            od: Length::new(12.0, INCHES),
+            thickness: Length::new(0.08, INCHES)
+        }
+    }
+
+    #[test]
+    fn chart_should_plot_diameter_against_length_in_selected_units() {
+        let chart = LengthChart::new(&roll(), INCHES, YARDS, false, &Translator::new());
+        assert_eq!("Diameter (in)", chart.x_label);
+        assert_eq!("Length (yrd)", chart.y_label);
+        let (min_x, max_x) = chart.x_range();
+        assert!((min_x - 4.0).abs() < 0.0001);
+        assert!((max_x - 12.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn swapped_chart_should_plot_length_on_x_axis() {
+        let chart = LengthChart::new(&roll(), INCHES, YARDS, true, &Translator::new());
+        assert_eq!("Length (yrd)", chart.x_label);
+        let (min_y, max_y) = chart.y_range();
+        assert!((min_y - 4.0).abs() < 0.0001);
+        assert!((max_y - 12.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn value_at_should_return_none_outside_of_curve() {
+        let chart = LengthChart::new(&roll(), INCHES, YARDS, false, &Translator::new());
+        assert!(chart.value_at(2.0).is_none());
+        assert!(chart.value_at(10.0).is_some());
+    }
+
+    #[test]
+    fn to_screen_should_map_curve_endpoints_to_rect_corners() {
+        let chart = LengthChart::new(&roll(), INCHES, YARDS, false, &Translator::new());
+        let rect = [10.0, 20.0, 100.0, 50.0];
+        let last = *chart.points.last().unwrap();
+        assert_eq!([10.0, 20.0], chart.to_screen(chart.points[0], rect));
+        assert_eq!([110.0, 70.0], chart.to_screen(last, rect));
+    }
+
+    #[test]
+    fn readout_should_use_number_locale() {
+        let chart = LengthChart::new(&roll(), INCHES, YARDS, false, &Translator::new());
+        assert_eq!("4.50 in -> 1.25 yrd", chart.format_readout([4.5, 1.25], &ENGLISH));
+        assert_eq!("4,50 in -> 1,25 yrd", chart.format_readout([4.5, 1.25], &GERMAN));
+    }
+}
@@ -1,17 +1,38 @@
 extern crate conrod;
 extern crate find_folder;
 extern crate glutin_window;
+extern crate graphics;
 extern crate opengl_graphics;
 extern crate piston;
 extern crate input;
 
 mod state;
+mod layout;
+mod theme;
+mod clipboard;
+mod cross_section;
+mod length_chart;
+mod comparison_table;
+mod planner_panel;
+mod label_panel;
+mod raster;
+mod headless;
 
 #[cfg(test)]
 mod test;
 
-use self::state::InputState;
-use ::estimator::units::{self, Unit, LengthUnit};
+use self::state::{InputState, AppMode, APP_MODES, CopyContent, FocusTarget, NavKey};
+use self::cross_section::CrossSection;
+use self::length_chart::LengthChart;
+use self::layout::{Layout, Rect};
+use self::theme::{FontStyle, Rgba, UiTheme};
+use ::estimator::units::{self, Unit, LengthUnit, UnitRegistry};
+use ::estimator::runtime::format_countdown;
+use ::label::LabelTemplate;
+
+use std::fmt;
+use std::io::{self, Write};
+use std::time::Instant;
 
 use std::path::{Path, PathBuf};
 
@@ -19,13 +40,14 @@ use self::glutin_window::GlutinWindow;
 use self::opengl_graphics::{GlGraphics, OpenGL};
 use self::opengl_graphics::glyph_cache::GlyphCache;
 use self::piston::event_loop::{Events, EventLoop};
-use self::piston::input::{RenderEvent};
+use self::piston::input::{Button as InputButton, GenericEvent, Key, PressEvent, ReleaseEvent, RenderEvent};
 use self::piston::window::{WindowSettings, Size};
 
 use self::conrod::{
     Background,
     Button,
     color,
+    Color,
     Colorable,
     CharacterCache,
     DropDownList,
@@ -39,6 +61,7 @@ use self::conrod::{
     Frameable,
     Positionable,
     TextBox,
+    Toggle,
     WidgetIndex,
     WidgetId
 };
@@ -53,18 +76,100 @@ widget_ids!{
     THICKNESS_LABEL,
     DIAMETER_UNIT_DROP_DOWN,
     THICKNESS_UNIT_DROP_DOWN,
+    MATERIAL_PRESET_DROP_DOWN,
     OUTPUT_UNIT_DROP_DOWN,
-    OUTPUT_DISPLAY
+    OUTPUT_AUTO_SCALE_TOGGLE,
+    OUTPUT_PRECISION_DROP_DOWN,
+    CURRENT_OD_INPUT_LABEL,
+    CURRENT_OD_INPUT_FIELD,
+    WIDTH_INPUT_LABEL,
+    WIDTH_INPUT_FIELD,
+    BASIS_WEIGHT_INPUT_LABEL,
+    BASIS_WEIGHT_INPUT_FIELD,
+    PRICE_INPUT_LABEL,
+    PRICE_INPUT_FIELD,
+    PRICE_BASIS_DROP_DOWN,
+    NUDGE_STEP_INPUT_LABEL,
+    NUDGE_STEP_INPUT_FIELD,
+    OUTPUT_DISPLAY,
+    COST_PER_LENGTH_DISPLAY,
+    COST_PER_AREA_DISPLAY,
+    LINE_SPEED_INPUT_LABEL,
+    LINE_SPEED_INPUT_FIELD,
+    SPLICE_OD_INPUT_LABEL,
+    SPLICE_OD_INPUT_FIELD,
+    RUN_TIME_DISPLAY,
+    SPLICE_TIME_DISPLAY,
+    RUN_START_BUTTON,
+    COPY_LENGTH_BUTTON,
+    COPY_SUMMARY_BUTTON,
+    COPY_STATUS_DISPLAY,
+    CROSS_SECTION_MATERIAL,
+    CROSS_SECTION_CONSUMED,
+    CROSS_SECTION_REMAINING,
+    CROSS_SECTION_RINGS with ::report::MAX_RINGS,
+    CROSS_SECTION_CORE,
+    CROSS_SECTION_LAYER_LABEL,
+    CHART_X_AXIS,
+    CHART_Y_AXIS,
+    CHART_CURVE,
+    CHART_X_LABEL,
+    CHART_Y_LABEL,
+    CHART_HOVER_MARKER,
+    CHART_READOUT,
+    CHART_SWAP_AXES_TOGGLE,
+    MODE_BUTTONS with 4,
+    LOCALE_DROP_DOWN,
+    LANGUAGE_DROP_DOWN,
+    THEME_DROP_DOWN,
+    TABLE_UNIT_LABEL,
+    TABLE_UNIT_DROP_DOWN,
+    TABLE_SORT_BUTTONS with 10,
+    TABLE_ADD_ROW_BUTTON,
+    TABLE_NAME_FIELDS with 8,
+    TABLE_THICKNESS_FIELDS with 8,
+    TABLE_OD_FIELDS with 8,
+    TABLE_ID_FIELDS with 8,
+    TABLE_WIDTH_FIELDS with 8,
+    TABLE_PRICE_FIELDS with 8,
+    TABLE_LENGTH_CELLS with 8,
+    TABLE_AREA_CELLS with 8,
+    TABLE_PRICE_PER_LENGTH_CELLS with 8,
+    TABLE_PRICE_PER_AREA_CELLS with 8,
+    PLAN_ROLL_SUMMARY,
+    PLAN_BY_PIECES_TOGGLE,
+    PLAN_LENGTH_LABEL,
+    PLAN_LENGTH_FIELD,
+    PLAN_PIECE_COUNT_LABEL,
+    PLAN_PIECE_COUNT_FIELD,
+    PLAN_CUT_LENGTH_LABEL,
+    PLAN_CUT_LENGTH_FIELD,
+    PLAN_SPLICE_LABEL,
+    PLAN_SPLICE_FIELD,
+    PLAN_RESULTS with 4,
+    LABEL_ROLL_SUMMARY,
+    LABEL_MATERIAL_LABEL,
+    LABEL_MATERIAL_FIELD,
+    LABEL_ROLL_ID_LABEL,
+    LABEL_ROLL_ID_FIELD,
+    LABEL_TEMPLATE_LABEL,
+    LABEL_TEMPLATE_DROP_DOWN,
+    LABEL_TARGET_LABEL,
+    LABEL_TARGET_FIELD,
+    LABEL_PRINT_BUTTON,
+    LABEL_STATUS_DISPLAY,
+    REPORT_PATH_LABEL,
+    REPORT_PATH_FIELD,
+    REPORT_SAVE_BUTTON,
+    REPORT_STATUS_DISPLAY
 }
 
 
-pub fn run() {
-    println!("thickness= {:?}, od= {:?}, id= {:?}", THICKNESS_CONTROL, OD_INPUT_FIELD, ID_INPUT_FIELD);
-
+pub fn run(registry: &UnitRegistry) {
     let opengl = OpenGL::V3_2;
     let window: GlutinWindow = WindowSettings::new(
             "Estimate Rolled Material Length".to_string(),
-            Size { width: 800, height: 300 }
+            Size { width: 1100, height: 520 }
         ).opengl(opengl)
         .exit_on_esc(true)
         .samples(4)
@@ -74,115 +179,620 @@ pub fn run() {
     let mut gl = GlGraphics::new(opengl);
     let assets: PathBuf = find_folder::Search::ParentsThenKids(3, 3)
         .for_folder("assets").unwrap();
-    let font_path: PathBuf = assets.join("fonts/NotoSans/NotoSans-Regular.ttf");
-    let theme = Theme::default();
-    let glyph_cache: GlyphCache = GlyphCache::new(&font_path).unwrap();
-    let ui = &mut Ui::new(glyph_cache, theme);
+    let new_ui = |font: FontStyle| {
+        let font_path: PathBuf = assets.join(font.path());
+        let glyph_cache: GlyphCache = GlyphCache::new(&font_path).unwrap();
+        Ui::new(glyph_cache, Theme::default())
+    };
 
-    let mut app_state: InputState = InputState::new();
-    let mut input_units = app_state.get_input_unit_strings();
+    let mut gui = Gui::new(registry, &assets, window.window.hidpi_factor() as f64);
+    let mut font = gui.app_state.get_theme().font;
+    let mut ui = new_ui(font);
 
     let event_iter = window.events().ups(180).max_fps(60);
 
     for event in event_iter {
-        ui.handle_event(&event);
+        gui.handle_event(&mut ui, &event);
 
         if let Some(args) = event.render_args() {
+            // The factor changes when the window is moved to a display with another density
+            gui.scale_factor = window.window.hidpi_factor() as f64;
             gl.draw(args.viewport(), |graphics_context, gl| {
-                create_ui(ui, &mut app_state, &mut input_units);
+                gui.update(&mut ui);
                 ui.draw_if_changed(graphics_context, gl);
             });
+
+            // Every widget is drawn with the Ui's one font, so switching to a theme with another
+            // font needs a new Ui. Text field focus is lost, but the inputs live in `app_state`
+            let theme_font = gui.app_state.get_theme().font;
+            if theme_font != font {
+                font = theme_font;
+                ui = new_ui(font);
+            }
+        }
+    }
+}
+
+/// Everything the interface keeps between frames apart from the window and renderer, so it can
+/// be driven by a real window or headlessly.
+pub struct Gui {
+    pub app_state: InputState,
+    input_units: Vec<String>,
+    nav_keys: Vec<NavKey>,
+    shift_held: bool,
+    ctrl_held: bool,
+    scale_factor: f64
+}
+
+impl Gui {
+
+    /// Sets up the interface with the registry's custom units and the translations, themes and
+    /// label templates found in `assets`.
+    pub fn new(registry: &UnitRegistry, assets: &Path, scale_factor: f64) -> Gui {
+        let mut app_state: InputState = InputState::new();
+        app_state.add_units(registry.custom_units());
+        match app_state.translator.load_dir(&assets.join("i18n")) {
+            Ok(skipped) => report_skipped(&skipped),
+            Err(err) => { let _ = writeln!(io::stderr(), "Translations unavailable: {}", err); }
+        }
+        match UiTheme::load_dir(&assets.join("themes")) {
+            Ok(themes) => {
+                report_skipped(&themes.skipped);
+                app_state.add_themes(themes.items);
+            }
+            Err(err) => { let _ = writeln!(io::stderr(), "Themes unavailable: {}", err); }
+        }
+        match LabelTemplate::load_dir(&assets.join("labels")) {
+            Ok(templates) => {
+                report_skipped(&templates.skipped);
+                app_state.add_label_templates(templates.items);
+            }
+            Err(err) => { let _ = writeln!(io::stderr(), "Label templates unavailable: {}", err); }
+        }
+
+        Gui {
+            input_units: app_state.get_input_unit_strings(),
+            app_state: app_state,
+            nav_keys: Vec::new(),
+            shift_held: false,
+            ctrl_held: false,
+            scale_factor: scale_factor
+        }
+    }
+
+    /// Passes an event to conrod, collects the keys used for keyboard navigation, and copies the
+    /// result on Ctrl+C, or the full summary on Ctrl+Shift+C. A mouse press clears the keyboard
+    /// focus.
+    pub fn handle_event<C, E>(&mut self, ui: &mut Ui<C>, event: &E) where C: CharacterCache, E: GenericEvent {
+        ui.handle_event(event);
+
+        // A click can move conrod's focus to any widget, so keyboard navigation starts over
+        // rather than nudging a control that no longer has focus
+        if let Some(InputButton::Mouse(_)) = event.press_args() {
+            self.app_state.focused = None;
+        }
+
+        if let Some(InputButton::Keyboard(key)) = event.press_args() {
+            if key == Key::LShift || key == Key::RShift {
+                self.shift_held = true;
+            }
+            if key == Key::LCtrl || key == Key::RCtrl {
+                self.ctrl_held = true;
+            }
+            if key == Key::C && self.ctrl_held {
+                let content = if self.shift_held { CopyContent::Summary } else { CopyContent::Length };
+                copy_to_clipboard(&mut self.app_state, content);
+            }
+            if let Some(nav_key) = to_nav_key(key, self.shift_held) {
+                self.nav_keys.push(nav_key);
+            }
         }
+        if let Some(InputButton::Keyboard(key)) = event.release_args() {
+            if key == Key::LShift || key == Key::RShift {
+                self.shift_held = false;
+            }
+            if key == Key::LCtrl || key == Key::RCtrl {
+                self.ctrl_held = false;
+            }
+        }
+    }
+
+    /// Sets every widget for one frame.
+    pub fn update<C>(&mut self, ui: &mut Ui<C>) where C: CharacterCache {
+        create_ui(ui, &mut self.app_state, &mut self.input_units, &mut self.nav_keys, self.scale_factor);
+    }
+}
+
+fn to_color(rgba: Rgba) -> Color {
+    color::rgba(rgba[0], rgba[1], rgba[2], rgba[3])
+}
+
+/// Copies the result or summary, leaving a message saying whether it worked.
+fn copy_to_clipboard(app_state: &mut InputState, content: CopyContent) {
+    let status = app_state.get_copy_text(content).map(|text| match clipboard::copy(&text) {
+        Ok(()) => app_state.translator.tr("status.copied"),
+        Err(err) => app_state.translator.tr_args("status.copy_failed", &[("error", &err.to_string())])
+    });
+    app_state.copy_status = status;
+}
+
+fn to_nav_key(key: Key, shift_held: bool) -> Option<NavKey> {
+    match key {
+        Key::Tab if shift_held => Some(NavKey::BackTab),
+        Key::Tab => Some(NavKey::Tab),
+        Key::Return | Key::NumPadEnter => Some(NavKey::Enter),
+        Key::Up => Some(NavKey::Up),
+        Key::Down => Some(NavKey::Down),
+        _ => None
     }
 }
 
-fn create_ui<C>(ui: &mut Ui<C>, app_state: &mut InputState, input_units: &mut Vec<String>)  where C: CharacterCache {
-    let vertical_spacing = 40.0;
-    let horizontal_pad = 25.0;
+/// Reports asset files that were skipped because they couldn't be loaded.
+fn report_skipped<E: fmt::Display>(skipped: &[(PathBuf, E)]) {
+    for &(ref path, ref err) in skipped.iter() {
+        let _ = writeln!(io::stderr(), "Skipped {}: {}", path.display(), err);
+    }
+}
+
+fn focus_widget(target: FocusTarget) -> WidgetId {
+    match target {
+        FocusTarget::Thickness => THICKNESS_CONTROL,
+        FocusTarget::ThicknessUnit => THICKNESS_UNIT_DROP_DOWN,
+        FocusTarget::Od => OD_INPUT_FIELD,
+        FocusTarget::DiameterUnit => DIAMETER_UNIT_DROP_DOWN,
+        FocusTarget::Id => ID_INPUT_FIELD,
+        FocusTarget::CurrentOd => CURRENT_OD_INPUT_FIELD,
+        FocusTarget::Width => WIDTH_INPUT_FIELD,
+        FocusTarget::BasisWeight => BASIS_WEIGHT_INPUT_FIELD,
+        FocusTarget::Price => PRICE_INPUT_FIELD,
+        FocusTarget::OutputUnit => OUTPUT_UNIT_DROP_DOWN
+    }
+}
 
-    let mut focus_next: Option<WidgetId> = None;
+/// Rows in the estimate view's inputs panel, from thickness down to the nudge step.
+const INPUT_ROWS: usize = 8;
+
+fn create_ui<C>(ui: &mut Ui<C>, app_state: &mut InputState, input_units: &mut Vec<String>, nav_keys: &mut Vec<NavKey>,
+        scale_factor: f64) where C: CharacterCache {
+    let layout = Layout::new(ui.win_w, ui.win_h, scale_factor);
+    let vertical_spacing = layout.row_spacing(INPUT_ROWS);
+    let horizontal_pad = layout.scaled(25.0);
+    let field_w = layout.scaled(120.0);
+    let field_h = layout.scaled(30.0);
+    let drop_down_w = layout.scaled(150.0);
+
+    let theme = app_state.get_theme().clone();
+    ui.theme.background_color = to_color(theme.background);
+    ui.theme.shape_color = to_color(theme.shape);
+    ui.theme.frame_color = to_color(theme.frame);
+    ui.theme.frame_width = theme.frame_width;
+    ui.theme.label_color = to_color(theme.label);
+
+    let default_theme = Theme::default();
+    ui.theme.font_size_large = layout.font_size(default_theme.font_size_large);
+    ui.theme.font_size_medium = layout.font_size(default_theme.font_size_medium);
+    ui.theme.font_size_small = layout.font_size(default_theme.font_size_small);
+
+    let pressed_keys = nav_keys.drain(..).collect::<Vec<NavKey>>();
+    *input_units = app_state.get_input_unit_strings();
 
     // Set the background color to use for clearing the screen.
-    Background::new().rgb(0.3, 0.4, 0.5).set(ui);
+    Background::new().color(to_color(theme.background)).set(ui);
+
+    let mut selected_mode: Option<AppMode> = None;
+    for (idx, mode) in APP_MODES.iter().enumerate() {
+        let slot = layout.header_controls[APP_MODES.len() - 1 - idx];
+        Button::new()
+            .label(&app_state.translator.tr(mode.message_key()))
+            .w_h(slot.width, slot.height)
+            .xy(slot.center()[0], slot.center()[1])
+            .react(|| selected_mode = Some(*mode))
+            .set(MODE_BUTTONS + idx, ui);
+    }
+
+    if let Some(mode) = selected_mode {
+        app_state.mode = mode;
+    }
+
+    let mut locales = app_state.get_locale_strings();
+    let mut selected_locale: Option<usize> = None;
+    let slot = layout.header_controls[APP_MODES.len()];
+    DropDownList::new(&mut locales, &mut app_state.number_locale)
+        .w_h(slot.width, slot.height)
+        .xy(slot.center()[0], slot.center()[1])
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            // Left to `select_locale`, which needs the previous locale to rewrite the inputs
+            selected_locale = Some(new_idx);
+        })
+        .set(LOCALE_DROP_DOWN, ui);
+
+    if let Some(idx) = selected_locale {
+        app_state.select_locale(idx);
+    }
+
+    let mut languages = app_state.translator.get_language_strings();
+    let mut selected_language: Option<usize> = None;
+    let slot = layout.header_controls[APP_MODES.len() + 1];
+    DropDownList::new(&mut languages, &mut app_state.translator.selected)
+        .w_h(slot.width, slot.height)
+        .xy(slot.center()[0], slot.center()[1])
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            // Left to `select_language`, which renames rows from the previous language
+            selected_language = Some(new_idx);
+        })
+        .set(LANGUAGE_DROP_DOWN, ui);
+
+    if let Some(idx) = selected_language {
+        app_state.select_language(idx);
+    }
+
+    let mut theme_names = app_state.get_theme_strings();
+    let slot = layout.header_controls[APP_MODES.len() + 2];
+    DropDownList::new(&mut theme_names, &mut app_state.selected_theme)
+        .w_h(slot.width, slot.height)
+        .xy(slot.center()[0], slot.center()[1])
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            *selected_idx = Some(new_idx);
+        })
+        .set(THEME_DROP_DOWN, ui);
+
+    if app_state.mode == AppMode::Compare {
+        comparison_table::draw_comparison_table(ui, app_state, &layout);
+        return;
+    }
+
+    if app_state.mode == AppMode::Plan {
+        planner_panel::draw_planner_panel(ui, app_state, &layout);
+        return;
+    }
+
+    if app_state.mode == AppMode::Label {
+        label_panel::draw_label_panel(ui, app_state, &layout);
+        return;
+    }
 
     // Seems like you have to manually compute x/y for first widget. This seems broken
-    let x: f64 = -(ui.win_w / 2.0) + 110.0;
-    let y: f64 = (ui.win_h / 2.0) - vertical_spacing;
+    let x: f64 = layout.inputs.left + layout.scaled(100.0);
+    let y: f64 = layout.inputs.top - vertical_spacing / 2.0;
 
-    Label::new("Material Thickness")
+    Label::new(&app_state.translator.tr("label.thickness"))
         .xy(x, y)
         .set(THICKNESS_LABEL, ui);
 
     TextBox::new(&mut app_state.thickness_input_value)
-        .react(|new_val: &mut String| {
-            fix_numeric_str(new_val);
-            focus_next = Some(OD_INPUT_FIELD);
-        })
+        .w_h(field_w, field_h)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
         .right_from(THICKNESS_LABEL, horizontal_pad)
         .align_middle_y()
         .set(THICKNESS_CONTROL, ui);
 
-    DropDownList::new(input_units, &mut app_state.selected_unit)
-        .right_from(THICKNESS_CONTROL, 20.0)
+    let mut thickness_units = app_state.get_thickness_unit_strings();
+    DropDownList::new(&mut thickness_units, &mut app_state.selected_thickness_unit)
+        .w_h(drop_down_w, field_h)
+        .right_from(THICKNESS_CONTROL, layout.scaled(20.0))
         .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
             *selected_idx = Some(new_idx);
         })
         .set(THICKNESS_UNIT_DROP_DOWN, ui);
 
-    Label::new("Outside Diameter")
+    let mut material_presets = app_state.get_material_preset_strings();
+    let mut selected_preset: Option<usize> = None;
+    DropDownList::new(&mut material_presets, &mut app_state.material_preset)
+        .w_h(drop_down_w, field_h)
+        .right_from(THICKNESS_UNIT_DROP_DOWN, layout.scaled(20.0))
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            *selected_idx = Some(new_idx);
+            selected_preset = Some(new_idx);
+        })
+        .set(MATERIAL_PRESET_DROP_DOWN, ui);
+
+    if let Some(idx) = selected_preset {
+        app_state.select_material_preset(idx);
+    }
+
+    Label::new(&app_state.translator.tr("label.od"))
         .down_from(THICKNESS_LABEL, vertical_spacing)
         .align_right()
         .set(OD_INPUT_LABEL, ui);
 
     TextBox::new(&mut app_state.od_input_value)
-        .react(|new_val: &mut String| {
-            fix_numeric_str(new_val);
-            focus_next = Some(ID_INPUT_FIELD);
-        })
+        .w_h(field_w, field_h)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
         .right_from(OD_INPUT_LABEL, horizontal_pad)
         .align_middle_y()
         .set(OD_INPUT_FIELD, ui);
 
+    DropDownList::new(input_units, &mut app_state.selected_unit)
+        .w_h(drop_down_w, field_h)
+        .right_from(OD_INPUT_FIELD, layout.scaled(20.0))
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            *selected_idx = Some(new_idx);
+        })
+        .set(DIAMETER_UNIT_DROP_DOWN, ui);
+
 
 
-    Label::new("Inside Diameter")
+    Label::new(&app_state.translator.tr("label.id"))
         .down_from(OD_INPUT_LABEL, vertical_spacing)
         .align_right()
         .set(ID_INPUT_LABEL, ui);
 
     TextBox::new(&mut app_state.id_input_value)
-        .react(|new_val: &mut String| {
-            fix_numeric_str(new_val);
-            focus_next = Some(THICKNESS_CONTROL);
-        })
+        .w_h(field_w, field_h)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
         .right_from(ID_INPUT_LABEL, horizontal_pad)
         .align_middle_y()
         .set(ID_INPUT_FIELD, ui);
 
-    let output_length = app_state.get_material_roll()
-        .map(|roll| {
-            roll.get_roll_length().convert_to(app_state.get_output_unit()).format()
-        }).unwrap_or_else(|| {
-        "##.##".to_string()
-    });
-
-    Label::new(&format!("Total Length: {}", &output_length))
-        .font_size(32)
+    Label::new(&app_state.translator.tr("label.current_od"))
         .down_from(ID_INPUT_LABEL, vertical_spacing)
-        .align_left()
+        .align_right()
+        .set(CURRENT_OD_INPUT_LABEL, ui);
+
+    TextBox::new(&mut app_state.current_od_input_value)
+        .w_h(field_w, field_h)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
+        .right_from(CURRENT_OD_INPUT_LABEL, horizontal_pad)
+        .align_middle_y()
+        .set(CURRENT_OD_INPUT_FIELD, ui);
+
+    Label::new(&app_state.translator.tr("label.width"))
+        .down_from(CURRENT_OD_INPUT_LABEL, vertical_spacing)
+        .align_right()
+        .set(WIDTH_INPUT_LABEL, ui);
+
+    TextBox::new(&mut app_state.width_input_value)
+        .w_h(field_w, field_h)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
+        .right_from(WIDTH_INPUT_LABEL, horizontal_pad)
+        .align_middle_y()
+        .set(WIDTH_INPUT_FIELD, ui);
+
+    Label::new(&app_state.translator.tr("label.basis_weight"))
+        .down_from(WIDTH_INPUT_LABEL, vertical_spacing)
+        .align_right()
+        .set(BASIS_WEIGHT_INPUT_LABEL, ui);
+
+    TextBox::new(&mut app_state.basis_weight_input_value)
+        .w_h(field_w, field_h)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
+        .right_from(BASIS_WEIGHT_INPUT_LABEL, horizontal_pad)
+        .align_middle_y()
+        .set(BASIS_WEIGHT_INPUT_FIELD, ui);
+
+    Label::new(&app_state.translator.tr("label.price"))
+        .down_from(BASIS_WEIGHT_INPUT_LABEL, vertical_spacing)
+        .align_right()
+        .set(PRICE_INPUT_LABEL, ui);
+
+    TextBox::new(&mut app_state.price_input_value)
+        .w_h(field_w, field_h)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
+        .right_from(PRICE_INPUT_LABEL, horizontal_pad)
+        .align_middle_y()
+        .set(PRICE_INPUT_FIELD, ui);
+
+    let mut price_bases = app_state.get_price_basis_strings();
+    DropDownList::new(&mut price_bases, &mut app_state.price_basis)
+        .w_h(drop_down_w, field_h)
+        .right_from(PRICE_INPUT_FIELD, layout.scaled(20.0))
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            *selected_idx = Some(new_idx);
+        })
+        .set(PRICE_BASIS_DROP_DOWN, ui);
+
+    Label::new(&app_state.translator.tr("label.nudge_step"))
+        .down_from(PRICE_INPUT_LABEL, vertical_spacing)
+        .align_right()
+        .set(NUDGE_STEP_INPUT_LABEL, ui);
+
+    TextBox::new(&mut app_state.nudge_step_input_value)
+        .w_h(field_w, field_h)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
+        .right_from(NUDGE_STEP_INPUT_LABEL, horizontal_pad)
+        .align_middle_y()
+        .set(NUDGE_STEP_INPUT_FIELD, ui);
+
+    let output_length = app_state.get_length_text().unwrap_or_else(|| "##.##".to_string());
+
+    // The unit drop down has a known width, so it anchors the summary to the panel's left edge
+    let (summary, run_time) = layout.result_panels();
+    let mut output_units = app_state.get_output_unit_strings();
+    DropDownList::new(&mut output_units, &mut app_state.selected_output_unit)
+        .w_h(drop_down_w, field_h)
+        .xy(summary.left + drop_down_w / 2.0, summary.top - layout.scaled(60.0))
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            *selected_idx = Some(new_idx);
+        })
+        .set(OUTPUT_UNIT_DROP_DOWN, ui);
+
+    Label::new(&app_state.translator.tr_args("label.total_length", &[("value", &output_length)]))
+        .font_size(layout.font_size(32))
+        .up_from(OUTPUT_UNIT_DROP_DOWN, layout.scaled(10.0))
+        .align_left_of(OUTPUT_UNIT_DROP_DOWN)
         .set(OUTPUT_DISPLAY, ui);
 
-    // if let Some(widget_id) = focus_next {
-    //     ui.change_focus_to(widget_id);
-    // }
+    let mut auto_scale = app_state.auto_scale_output;
+    Toggle::new(auto_scale)
+        .label(&app_state.translator.tr("toggle.auto_scale"))
+        .w_h(layout.scaled(110.0), field_h)
+        .right_from(OUTPUT_UNIT_DROP_DOWN, layout.scaled(20.0))
+        .react(|new_val: bool| {
+            auto_scale = new_val;
+        })
+        .set(OUTPUT_AUTO_SCALE_TOGGLE, ui);
+    app_state.auto_scale_output = auto_scale;
+
+    let mut precisions = app_state.get_output_precision_strings();
+    DropDownList::new(&mut precisions, &mut app_state.output_precision)
+        .w_h(drop_down_w, field_h)
+        .right_from(OUTPUT_AUTO_SCALE_TOGGLE, layout.scaled(20.0))
+        .react(|selected_idx: &mut Option<usize>, new_idx: usize, val: &str| {
+            *selected_idx = Some(new_idx);
+        })
+        .set(OUTPUT_PRECISION_DROP_DOWN, ui);
+
+    let (cost_per_length, cost_per_area) = app_state.get_cost_estimate()
+        .map(|cost| (cost.format_per_length(&app_state.get_locale()), cost.format_per_area(&app_state.get_locale())))
+        .unwrap_or_else(|| ("--".to_string(), "--".to_string()));
+
+    Label::new(&app_state.translator.tr_args("label.cost", &[("value", &cost_per_length)]))
+        .down_from(OUTPUT_UNIT_DROP_DOWN, layout.scaled(15.0))
+        .align_left_of(OUTPUT_UNIT_DROP_DOWN)
+        .set(COST_PER_LENGTH_DISPLAY, ui);
+
+    Label::new(&app_state.translator.tr_args("label.cost", &[("value", &cost_per_area)]))
+        .right_from(COST_PER_LENGTH_DISPLAY, horizontal_pad)
+        .align_middle_y()
+        .set(COST_PER_AREA_DISPLAY, ui);
+
+    let mut copy_content: Option<CopyContent> = None;
+    Button::new()
+        .label(&app_state.translator.tr("button.copy_length"))
+        .w_h(layout.scaled(130.0), field_h)
+        .down_from(COST_PER_LENGTH_DISPLAY, layout.scaled(15.0))
+        .align_left_of(OUTPUT_UNIT_DROP_DOWN)
+        .react(|| copy_content = Some(CopyContent::Length))
+        .set(COPY_LENGTH_BUTTON, ui);
+
+    Button::new()
+        .label(&app_state.translator.tr("button.copy_summary"))
+        .w_h(layout.scaled(160.0), field_h)
+        .right_from(COPY_LENGTH_BUTTON, layout.scaled(10.0))
+        .align_middle_y()
+        .react(|| copy_content = Some(CopyContent::Summary))
+        .set(COPY_SUMMARY_BUTTON, ui);
+
+    if let Some(content) = copy_content {
+        copy_to_clipboard(app_state, content);
+    }
+
+    if let Some(ref status) = app_state.copy_status {
+        Label::new(status)
+            .right_from(COPY_SUMMARY_BUTTON, horizontal_pad)
+            .align_middle_y()
+            .set(COPY_STATUS_DISPLAY, ui);
+    }
+
+    draw_run_time(ui, app_state, &layout, run_time);
+
+    let (cross_section_area, chart_area) = layout.visual_panels();
+    if let Some(roll) = app_state.get_material_roll() {
+        // Leave room below the circle for the layer count label
+        let label_room = layout.scaled(20.0);
+        let radius = (cross_section_area.width / 2.0).min((cross_section_area.height - label_room) / 2.0) - layout.scaled(10.0);
+        let current_od = app_state.get_current_od();
+        let cross_section = CrossSection::new(&roll, current_od.as_ref(), radius.max(1.0));
+        let center = cross_section_area.center();
+        cross_section::draw_cross_section(ui, &cross_section, center[0], center[1] + label_room / 2.0,
+                &layout, &app_state.translator, &theme);
+
+        // Leave room for the axis labels and readout above the chart, and the swap toggle below
+        let chart = LengthChart::new(&roll, app_state.get_input_unit(), app_state.get_output_unit(),
+                app_state.chart_swap_axes, &app_state.translator);
+        let chart_rect = Rect::new(chart_area.left + layout.scaled(30.0), chart_area.top - layout.scaled(50.0),
+                (chart_area.width - layout.scaled(50.0)).max(1.0), (chart_area.height - layout.scaled(110.0)).max(1.0));
+        length_chart::draw_length_chart(ui, &chart, chart_rect.to_bounds(), &layout,
+                &app_state.get_locale(), &theme);
+    }
+
+    let mut swap_axes = app_state.chart_swap_axes;
+    Toggle::new(swap_axes)
+        .label(&app_state.translator.tr("toggle.swap_axes"))
+        .w_h(layout.scaled(110.0), field_h)
+        .xy(chart_area.center()[0], chart_area.bottom() + layout.scaled(20.0))
+        .react(|new_val: bool| {
+            swap_axes = new_val;
+        })
+        .set(CHART_SWAP_AXES_TOGGLE, ui);
+    app_state.chart_swap_axes = swap_axes;
+
+    let focused_before = app_state.focused;
+    for key in pressed_keys {
+        app_state.handle_key(key);
+    }
+    if app_state.focused != focused_before {
+        if let Some(target) = app_state.focused {
+            ui.change_focus_to(focus_widget(target));
+        }
+    }
+}
+
+/// Draws the line speed inputs and a countdown to the roll running empty, and to the point where
+/// the next splice should be prepared.
+fn draw_run_time<C>(ui: &mut Ui<C>, app_state: &mut InputState, layout: &Layout, area: Rect) where C: CharacterCache {
+    let vertical_spacing = layout.scaled(35.0);
+    let horizontal_pad = layout.scaled(15.0);
+
+    Label::new(&app_state.translator.tr_args("label.line_speed", &[("unit", app_state.get_output_unit().abbrev())]))
+        .xy(area.left + layout.scaled(110.0), area.top - layout.scaled(20.0))
+        .set(LINE_SPEED_INPUT_LABEL, ui);
+
+    TextBox::new(&mut app_state.line_speed_input_value)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
+        .w_h(layout.scaled(80.0), layout.scaled(30.0))
+        .right_from(LINE_SPEED_INPUT_LABEL, horizontal_pad)
+        .align_middle_y()
+        .set(LINE_SPEED_INPUT_FIELD, ui);
+
+    Label::new(&app_state.translator.tr("label.splice_od"))
+        .down_from(LINE_SPEED_INPUT_LABEL, vertical_spacing)
+        .align_right()
+        .set(SPLICE_OD_INPUT_LABEL, ui);
+
+    TextBox::new(&mut app_state.splice_od_input_value)
+        .react(|new_val: &mut String| fix_numeric_str(new_val))
+        .w_h(layout.scaled(80.0), layout.scaled(30.0))
+        .right_from(SPLICE_OD_INPUT_LABEL, horizontal_pad)
+        .align_middle_y()
+        .set(SPLICE_OD_INPUT_FIELD, ui);
+
+    let run_time = app_state.get_time_to_empty()
+        .map(|estimate| format_countdown(&app_state.count_down(estimate)))
+        .unwrap_or_else(|| "-:--:--".to_string());
+    let splice_time = app_state.get_time_to_splice()
+        .map(|estimate| format_countdown(&app_state.count_down(estimate)))
+        .unwrap_or_else(|| "-:--:--".to_string());
+
+    Label::new(&app_state.translator.tr_args("label.empty_in", &[("time", &run_time)]))
+        .font_size(layout.font_size(20))
+        .down_from(SPLICE_OD_INPUT_LABEL, vertical_spacing)
+        .align_left()
+        .set(RUN_TIME_DISPLAY, ui);
+
+    Label::new(&app_state.translator.tr_args("label.splice_in", &[("time", &splice_time)]))
+        .font_size(layout.font_size(20))
+        .down_from(RUN_TIME_DISPLAY, layout.scaled(10.0))
+        .align_left()
+        .set(SPLICE_TIME_DISPLAY, ui);
+
+    let mut toggle_run = false;
+    let run_label = app_state.translator.tr(if app_state.run_started.is_some() { "button.stop" } else { "button.start" });
+    Button::new()
+        .label(&run_label)
+        .w_h(layout.scaled(80.0), layout.scaled(30.0))
+        .right_from(RUN_TIME_DISPLAY, horizontal_pad)
+        .align_middle_y()
+        .react(|| toggle_run = true)
+        .set(RUN_START_BUTTON, ui);
+
+    if toggle_run {
+        app_state.run_started = match app_state.run_started {
+            Some(_) => None,
+            None => Some(Instant::now())
+        };
+    }
 }
 
+/// Truncates the input at the first character that can't be part of a number in any locale.
+/// Separators are left for the locale to interpret, including a space or no-break space used
+/// as a thousands separator.
 #[allow(unused_variables)]
 fn fix_numeric_str(input: &mut String) {
     let number_base = 10;
 
     let invalid_char: Option<(usize, char)> = input.char_indices().find(|&(idx, ch)| {
-        !(ch.is_digit(number_base) || ch == '.')
+        !(ch.is_digit(number_base) || ch == '.' || ch == ',' || ch == ' ' || ch == '\u{a0}')
         });
 
     match invalid_char {
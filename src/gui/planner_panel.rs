@@ -0,0 +1,170 @@
+use ::estimator::locale::NumberLocale;
+use ::estimator::planner::JobPlan;
+use ::estimator::units::{Length, LengthUnit, Unit};
+use ::i18n::Translator;
+
+use super::layout::Layout;
+use super::state::InputState;
+use super::fix_numeric_str;
+
+use super::conrod::{
+    CharacterCache,
+    Label,
+    Labelable,
+    Positionable,
+    Sizeable,
+    TextBox,
+    Toggle,
+    Ui,
+    Widget
+};
+
+use super::{
+    PLAN_ROLL_SUMMARY,
+    PLAN_BY_PIECES_TOGGLE,
+    PLAN_LENGTH_LABEL,
+    PLAN_LENGTH_FIELD,
+    PLAN_PIECE_COUNT_LABEL,
+    PLAN_PIECE_COUNT_FIELD,
+    PLAN_CUT_LENGTH_LABEL,
+    PLAN_CUT_LENGTH_FIELD,
+    PLAN_SPLICE_LABEL,
+    PLAN_SPLICE_FIELD,
+    PLAN_RESULTS
+};
+
+/// Formats the lines shown in the results section of the planner.
+pub fn format_plan(plan: &JobPlan, unit: LengthUnit, locale: &NumberLocale, translator: &Translator) -> Vec<String> {
+    let format_length = |length: &Length| locale.format_length(&length.convert_to(unit.clone()));
+    let mut lines = vec![
+        translator.tr_args("plan.rolls_to_pull", &[("count", &plan.rolls_required.to_string())]),
+        translator.tr_args("plan.leftover", &[("length", &format_length(&plan.leftover_on_last_roll))])
+    ];
+
+    match plan.pieces_per_roll {
+        Some(pieces) => {
+            lines.push(translator.tr_args("plan.pieces_per_roll", &[("count", &pieces.to_string())]));
+            lines.push(translator.tr_args("plan.offcut_waste", &[("length", &format_length(&plan.offcut_waste))]));
+        }
+        None => {
+            lines.push(translator.tr_args("plan.splices", &[("count", &plan.splice_count.to_string())]));
+            lines.push(translator.tr_args("plan.splice_waste", &[("length", &format_length(&plan.splice_waste))]));
+        }
+    }
+    lines
+}
+
+/// Draws the job planner in the layout's body. It uses the roll entered in the single roll view
+/// as the spec for every roll pulled.
+pub fn draw_planner_panel<C>(ui: &mut Ui<C>, app_state: &mut InputState, layout: &Layout) where C: CharacterCache {
+    let (left, top) = (layout.body.left, layout.body.top);
+    let vertical_spacing = layout.scaled(40.0);
+    let horizontal_pad = layout.scaled(25.0);
+    let output_unit = app_state.get_output_unit();
+    let locale = app_state.get_locale();
+    let translator = &app_state.translator;
+
+    let roll_summary = app_state.get_material_roll().map(|roll| {
+        translator.tr_args("plan.roll_summary", &[
+            ("id", &locale.format_length(&roll.id)),
+            ("od", &locale.format_length(&roll.od)),
+            ("length", &locale.format_length(&roll.get_roll_length().convert_to(output_unit.clone())))
+        ])
+    }).unwrap_or_else(|| translator.tr("plan.no_roll"));
+
+    Label::new(&roll_summary)
+        .xy(left + layout.scaled(250.0).min(layout.body.width / 2.0), top)
+        .set(PLAN_ROLL_SUMMARY, ui);
+
+    let mut by_pieces = app_state.job_by_pieces;
+    Toggle::new(by_pieces)
+        .label(&translator.tr("plan.cut_pieces"))
+        .w_h(layout.scaled(130.0), layout.scaled(30.0))
+        .down_from(PLAN_ROLL_SUMMARY, vertical_spacing)
+        .x(left + layout.scaled(65.0))
+        .react(|new_val: bool| by_pieces = new_val)
+        .set(PLAN_BY_PIECES_TOGGLE, ui);
+    app_state.job_by_pieces = by_pieces;
+
+    if app_state.job_by_pieces {
+        Label::new(&translator.tr("plan.piece_count"))
+            .down_from(PLAN_BY_PIECES_TOGGLE, vertical_spacing)
+            .set(PLAN_PIECE_COUNT_LABEL, ui);
+
+        TextBox::new(&mut app_state.job_piece_count_input_value)
+            .react(|new_val: &mut String| fix_numeric_str(new_val))
+            .right_from(PLAN_PIECE_COUNT_LABEL, horizontal_pad)
+            .align_middle_y()
+            .set(PLAN_PIECE_COUNT_FIELD, ui);
+
+        Label::new(&translator.tr_args("plan.cut_length", &[("unit", output_unit.abbrev())]))
+            .down_from(PLAN_PIECE_COUNT_LABEL, vertical_spacing)
+            .set(PLAN_CUT_LENGTH_LABEL, ui);
+
+        TextBox::new(&mut app_state.job_cut_length_input_value)
+            .react(|new_val: &mut String| fix_numeric_str(new_val))
+            .right_from(PLAN_CUT_LENGTH_LABEL, horizontal_pad)
+            .align_middle_y()
+            .set(PLAN_CUT_LENGTH_FIELD, ui);
+    } else {
+        Label::new(&translator.tr_args("plan.job_length", &[("unit", output_unit.abbrev())]))
+            .down_from(PLAN_BY_PIECES_TOGGLE, vertical_spacing)
+            .set(PLAN_LENGTH_LABEL, ui);
+
+        TextBox::new(&mut app_state.job_length_input_value)
+            .react(|new_val: &mut String| fix_numeric_str(new_val))
+            .right_from(PLAN_LENGTH_LABEL, horizontal_pad)
+            .align_middle_y()
+            .set(PLAN_LENGTH_FIELD, ui);
+
+        Label::new(&translator.tr_args("plan.splice_allowance", &[("unit", output_unit.abbrev())]))
+            .down_from(PLAN_LENGTH_LABEL, vertical_spacing)
+            .set(PLAN_SPLICE_LABEL, ui);
+
+        TextBox::new(&mut app_state.splice_allowance_input_value)
+            .react(|new_val: &mut String| fix_numeric_str(new_val))
+            .right_from(PLAN_SPLICE_LABEL, horizontal_pad)
+            .align_middle_y()
+            .set(PLAN_SPLICE_FIELD, ui);
+    }
+
+    let lines = app_state.get_job_plan()
+        .map(|plan| format_plan(&plan, output_unit.clone(), &locale, translator))
+        .unwrap_or_else(|| vec![translator.tr_args("plan.rolls_to_pull", &[("count", "--")])]);
+
+    let results_top = top - vertical_spacing * 5.0;
+    for (idx, line) in lines.iter().enumerate() {
+        Label::new(line)
+            .font_size(layout.font_size(20))
+            .xy(left + layout.scaled(200.0).min(layout.body.width / 2.0), results_top - layout.scaled(30.0) * idx as f64)
+            .set(PLAN_RESULTS + idx, ui);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::locale::{ENGLISH, GERMAN};
+    use ::estimator::planner::JobPlan;
+    use ::estimator::units::{Length, CENTIMETERS, METERS};
+    use ::i18n::Translator;
+
+    #[test]
+    fn format_plan_should_show_splices_for_continuous_jobs() {
+        let plan = JobPlan {
+            rolls_required: 3,
+            leftover_on_last_roll: Length::new(150.0, CENTIMETERS),
+            splice_count: 2,
+            splice_waste: Length::new(20.0, CENTIMETERS),
+            pieces_per_roll: None,
+            offcut_waste: Length::zero()
+        };
+
+        let lines = format_plan(&plan, METERS, &ENGLISH, &Translator::new());
+        assert_eq!("Rolls to pull: 3", lines[0]);
+        assert_eq!("Leftover on last roll: 1.50 m", lines[1]);
+        assert_eq!("Splices: 2", lines[2]);
+        assert_eq!("Splice waste: 0.20 m", lines[3]);
+        assert_eq!("Leftover on last roll: 1,50 m", format_plan(&plan, METERS, &GERMAN, &Translator::new())[1]);
+    }
+}
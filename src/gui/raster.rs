@@ -0,0 +1,199 @@
+//! A small software rasterizer, so the interface can be drawn without a window or GPU and the
+//! result compared against reference screenshots. Images are read and written as binary PPM,
+//! which needs no image library and opens in most viewers.
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// An RGB image, with its origin at the top left.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<[u8; 3]>
+}
+
+fn to_byte(channel: f32) -> u8 {
+    (channel.max(0.0).min(1.0) * 255.0).round() as u8
+}
+
+fn edge(a: [f64; 2], b: [f64; 2], p: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+impl Canvas {
+
+    pub fn new(width: u32, height: u32) -> Canvas {
+        Canvas {
+            width: width,
+            height: height,
+            pixels: vec![[0, 0, 0]; (width * height) as usize]
+        }
+    }
+
+    pub fn clear(&mut self, color: [f32; 4]) {
+        let rgb = [to_byte(color[0]), to_byte(color[1]), to_byte(color[2])];
+        for pixel in self.pixels.iter_mut() {
+            *pixel = rgb;
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Blends `color` over the pixel at `(x, y)` using its alpha.
+    pub fn blend(&mut self, x: u32, y: u32, color: [f32; 4]) {
+        let idx = (y * self.width + x) as usize;
+        let alpha = color[3].max(0.0).min(1.0);
+        let old = self.pixels[idx];
+        let mut blended = [0u8; 3];
+        for channel in 0..3 {
+            blended[channel] = to_byte(color[channel] * alpha + old[channel] as f32 / 255.0 * (1.0 - alpha));
+        }
+        self.pixels[idx] = blended;
+    }
+
+    /// Fills the triangle with corners given in pixel coordinates, covering each pixel whose
+    /// center lies inside it.
+    pub fn fill_triangle(&mut self, corners: [[f64; 2]; 3], color: [f32; 4]) {
+        let (a, b, c) = (corners[0], corners[1], corners[2]);
+        let area = edge(a, b, c);
+        if area == 0.0 {
+            return;
+        }
+
+        let min_x = a[0].min(b[0]).min(c[0]).floor().max(0.0) as u32;
+        let min_y = a[1].min(b[1]).min(c[1]).floor().max(0.0) as u32;
+        let max_x = a[0].max(b[0]).max(c[0]).ceil().min(self.width as f64) as u32;
+        let max_y = a[1].max(b[1]).max(c[1]).ceil().min(self.height as f64) as u32;
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let center = [x as f64 + 0.5, y as f64 + 0.5];
+                let weights = [edge(b, c, center) / area, edge(c, a, center) / area, edge(a, b, center) / area];
+                if weights.iter().all(|weight| *weight >= 0.0) {
+                    self.blend(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// The fraction of pixels, between 0 and 1, where any channel differs from `other` by more
+    /// than `tolerance`, or `None` if the images are different sizes.
+    pub fn difference(&self, other: &Canvas, tolerance: u8) -> Option<f64> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+
+        let differing = self.pixels.iter().zip(other.pixels.iter())
+            .filter(|&(mine, theirs)| {
+                (0..3).any(|channel| (mine[channel] as i16 - theirs[channel] as i16).abs() > tolerance as i16)
+            })
+            .count();
+        Some(differing as f64 / self.pixels.len().max(1) as f64)
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for pixel in self.pixels.iter() {
+            bytes.extend_from_slice(pixel);
+        }
+        bytes
+    }
+
+    /// Reads a binary PPM with a maximum value of 255, as written by `to_ppm`.
+    pub fn from_ppm(bytes: &[u8]) -> Option<Canvas> {
+        // The header is four whitespace separated fields followed by a single whitespace byte
+        let mut fields: Vec<String> = Vec::new();
+        let mut pos = 0;
+        while fields.len() < 4 {
+            while pos < bytes.len() && (bytes[pos] as char).is_whitespace() {
+                pos += 1;
+            }
+            let start = pos;
+            while pos < bytes.len() && !(bytes[pos] as char).is_whitespace() {
+                pos += 1;
+            }
+            if start == pos {
+                return None;
+            }
+            fields.push(String::from_utf8_lossy(&bytes[start..pos]).into_owned());
+        }
+        pos += 1;
+
+        let width = fields[1].parse::<u32>().ok();
+        let height = fields[2].parse::<u32>().ok();
+        match (fields[0].as_str(), width, height, fields[3].as_str()) {
+            ("P6", Some(width), Some(height), "255") if bytes.len() >= pos + (width * height * 3) as usize => {
+                let pixels = bytes[pos..pos + (width * height * 3) as usize].chunks(3)
+                    .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+                    .collect::<Vec<[u8; 3]>>();
+                Some(Canvas { width: width, height: height, pixels: pixels })
+            }
+            _ => None
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        try!(File::create(path)).write_all(&self.to_ppm())
+    }
+
+    /// Loads a PPM image, returning `None` if it can't be read or isn't a binary PPM.
+    pub fn load(path: &Path) -> Option<Canvas> {
+        let mut bytes = Vec::new();
+        File::open(path).and_then(|mut file| file.read_to_end(&mut bytes)).ok()
+            .and_then(|_| Canvas::from_ppm(&bytes))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+    #[test]
+    fn triangle_should_cover_pixels_inside_it_in_either_winding() {
+        let mut canvas = Canvas::new(10, 10);
+        canvas.fill_triangle([[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]], RED);
+        assert_eq!([255, 0, 0], canvas.pixel(1, 1));
+        assert_eq!([0, 0, 0], canvas.pixel(8, 8));
+
+        let mut reversed = Canvas::new(10, 10);
+        reversed.fill_triangle([[0.0, 0.0], [0.0, 10.0], [10.0, 0.0]], RED);
+        assert_eq!(canvas, reversed);
+    }
+
+    #[test]
+    fn translucent_colors_should_blend_with_background() {
+        let mut canvas = Canvas::new(2, 2);
+        canvas.clear([1.0, 1.0, 1.0, 1.0]);
+        canvas.blend(0, 0, [0.0, 0.0, 0.0, 0.5]);
+        assert_eq!([128, 128, 128], canvas.pixel(0, 0));
+        assert_eq!([255, 255, 255], canvas.pixel(1, 1));
+    }
+
+    #[test]
+    fn ppm_should_round_trip() {
+        let mut canvas = Canvas::new(4, 3);
+        canvas.clear([0.2, 0.4, 0.6, 1.0]);
+        canvas.blend(3, 2, RED);
+        assert_eq!(Some(canvas.clone()), Canvas::from_ppm(&canvas.to_ppm()));
+        assert_eq!(None, Canvas::from_ppm(b"P3\n4 3\n255\n"));
+    }
+
+    #[test]
+    fn difference_should_count_pixels_beyond_tolerance() {
+        let mut first = Canvas::new(2, 2);
+        let mut second = Canvas::new(2, 2);
+        first.clear([0.5, 0.5, 0.5, 1.0]);
+        second.clear([0.5, 0.5, 0.5, 1.0]);
+        second.blend(0, 0, [0.51, 0.5, 0.5, 1.0]);
+        second.blend(1, 1, RED);
+        assert_eq!(Some(0.5), first.difference(&second, 0));
+        assert_eq!(Some(0.25), first.difference(&second, 5));
+        assert_eq!(None, first.difference(&Canvas::new(3, 2), 5));
+    }
+}
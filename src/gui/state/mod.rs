@@ -2,16 +2,233 @@
 mod test;
 
 use estimator;
-use estimator::units::{self, Unit, LengthUnit, Length, parse_str};
+use estimator::comparison::{self, RollFormat, RollMetrics, SortColumn};
+use estimator::cost::{CostEstimate, Price};
+use estimator::display::{DisplayFormat, Precision, UnitSystem};
+use estimator::locale::{NumberLocale, LOCALES};
+use estimator::planner::{self, JobPlan, JobRequirement};
+use estimator::quantities::{BasisWeight, Mass, Speed, Time, GRAMS_PER_SQUARE_METER, MINUTES};
+use estimator::runtime;
+
+use std::time::{Duration, Instant};
+use estimator::units::{self, Unit, LengthUnit, Length};
+use i18n::Translator;
+use label::{LabelTemplate, PrintTarget, RollLabel};
+use report::{self, RollReport, SummaryInputs};
 use super::conrod::WidgetId;
+use super::theme::UiTheme;
+
+/// Text inputs for one row of the roll comparison table.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RollRowInput {
+    pub name: String,
+    pub thickness: String,
+    pub od: String,
+    pub id: String,
+    pub width: String,
+    pub price_per_roll: String
+}
+
+impl RollRowInput {
+
+    pub fn new(name: &str) -> RollRowInput {
+        RollRowInput {
+            name: name.to_string(),
+            thickness: "0.08".to_string(),
+            od: "12.00".to_string(),
+            id: "4.00".to_string(),
+            width: "60.00".to_string(),
+            price_per_roll: String::new()
+        }
+    }
+
+    pub fn to_roll_format(&self, unit: LengthUnit, locale: &NumberLocale) -> Option<RollFormat> {
+        let price = self.price_per_roll.trim();
+        let price_per_roll = if price.is_empty() {
+            None
+        } else {
+            match locale.parse_number(price) {
+                Some(val) => Some(val),
+                None => return None
+            }
+        };
+
+        let width_opt = locale.parse_length(&self.width, unit.clone()).and_then(|width| {
+            if width > Length::zero() { Some(width) } else { None }
+        });
+
+        parse_material_roll(&self.thickness, &self.id, &self.od, unit.clone(), unit, locale).and_then(|roll| {
+            width_opt.map(|width| {
+                RollFormat {
+                    name: self.name.clone(),
+                    roll: roll,
+                    width: width,
+                    basis_weight: None,
+                    price: price_per_roll.map(Price::PerRoll)
+                }
+            })
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AppMode {
+    Estimate,
+    Compare,
+    Plan,
+    Label
+}
+
+pub const APP_MODES: [AppMode; 4] = [AppMode::Estimate, AppMode::Compare, AppMode::Plan, AppMode::Label];
+
+impl AppMode {
+
+    pub fn message_key(&self) -> &'static str {
+        match *self {
+            AppMode::Estimate => "mode.estimate",
+            AppMode::Compare => "mode.compare",
+            AppMode::Plan => "mode.plan",
+            AppMode::Label => "mode.label"
+        }
+    }
+}
+
+/// Kinds of material with their own conventions for specifying thickness.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MaterialPreset {
+    Custom,
+    Fabric,
+    Film,
+    Paper
+}
+
+pub const MATERIAL_PRESETS: [MaterialPreset; 4] = [MaterialPreset::Custom, MaterialPreset::Fabric, MaterialPreset::Film, MaterialPreset::Paper];
 
+impl MaterialPreset {
+
+    pub fn message_key(&self) -> &'static str {
+        match *self {
+            MaterialPreset::Custom => "preset.custom",
+            MaterialPreset::Fabric => "preset.fabric",
+            MaterialPreset::Film => "preset.film",
+            MaterialPreset::Paper => "preset.paper"
+        }
+    }
+
+    /// The unit this kind of material's thickness is normally quoted in, if it has one.
+    pub fn thickness_unit(&self) -> Option<LengthUnit> {
+        match *self {
+            MaterialPreset::Film => Some(units::GAUGE),
+            MaterialPreset::Paper => Some(units::CALIPER_POINTS),
+            MaterialPreset::Custom | MaterialPreset::Fabric => None
+        }
+    }
+}
+
+/// Controls of the single roll view that can be reached with the keyboard.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FocusTarget {
+    Thickness,
+    ThicknessUnit,
+    Od,
+    DiameterUnit,
+    Id,
+    CurrentOd,
+    Width,
+    BasisWeight,
+    Price,
+    OutputUnit
+}
+
+/// The order Tab moves through the controls.
+pub const FOCUS_ORDER: [FocusTarget; 10] = [
+    FocusTarget::Thickness,
+    FocusTarget::ThicknessUnit,
+    FocusTarget::Od,
+    FocusTarget::DiameterUnit,
+    FocusTarget::Id,
+    FocusTarget::CurrentOd,
+    FocusTarget::Width,
+    FocusTarget::BasisWeight,
+    FocusTarget::Price,
+    FocusTarget::OutputUnit
+];
+
+/// Keys used to operate the single roll view without a mouse.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NavKey {
+    Tab,
+    BackTab,
+    Enter,
+    Up,
+    Down
+}
+
+/// What the copy actions put on the clipboard.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CopyContent {
+    Length,
+    Summary
+}
+
+/// Message keys for the entries of `cost::PRICE_BASIS_NAMES`.
+const PRICE_BASIS_MESSAGES: [&'static str; 3] = ["price.per_roll", "price.per_kg", "price.per_sq_m"];
+
+/// Precisions offered for auto-scaled results.
+pub const OUTPUT_PRECISIONS: [Precision; 5] = [
+    Precision::SignificantFigures(3),
+    Precision::SignificantFigures(4),
+    Precision::SignificantFigures(6),
+    Precision::Decimals(2),
+    Precision::Decimals(3)
+];
 
 pub struct InputState {
     pub thickness_input_value: String,
     pub od_input_value: String,
     pub id_input_value: String,
+    pub current_od_input_value: String,
+    pub width_input_value: String,
+    pub basis_weight_input_value: String,
+    pub price_input_value: String,
+    pub price_basis: Option<usize>,
     pub valid_units: Vec<LengthUnit>,
-    pub selected_unit: Option<usize>
+    pub selected_unit: Option<usize>,
+    pub valid_thickness_units: Vec<LengthUnit>,
+    pub selected_thickness_unit: Option<usize>,
+    pub material_preset: Option<usize>,
+    pub valid_output_units: Vec<LengthUnit>,
+    pub selected_output_unit: Option<usize>,
+    pub auto_scale_output: bool,
+    pub output_precision: Option<usize>,
+    pub number_locale: Option<usize>,
+    pub translator: Translator,
+    pub themes: Vec<UiTheme>,
+    pub selected_theme: Option<usize>,
+    pub chart_swap_axes: bool,
+    pub mode: AppMode,
+    pub comparison_rows: Vec<RollRowInput>,
+    pub sort_column: SortColumn,
+    pub sort_ascending: bool,
+    pub job_by_pieces: bool,
+    pub job_length_input_value: String,
+    pub job_piece_count_input_value: String,
+    pub job_cut_length_input_value: String,
+    pub splice_allowance_input_value: String,
+    pub line_speed_input_value: String,
+    pub splice_od_input_value: String,
+    pub run_started: Option<Instant>,
+    pub focused: Option<FocusTarget>,
+    pub nudge_step_input_value: String,
+    pub copy_status: Option<String>,
+    pub label_material_input_value: String,
+    pub label_roll_id_input_value: String,
+    pub label_target_input_value: String,
+    pub label_templates: Vec<LabelTemplate>,
+    pub selected_label_template: Option<usize>,
+    pub label_status: Option<String>,
+    pub report_path_input_value: String,
+    pub report_status: Option<String>
 }
 
 impl InputState {
@@ -21,13 +238,71 @@ impl InputState {
         let thickness_val = 0.08;
         let od_val = 12.0;
         let id_val = 4.0;
+        let translator = Translator::new();
+        let first_row_name = default_row_name(&translator, 1);
 
         InputState {
             thickness_input_value: format!("{:.2}", thickness_val).to_string(),
             od_input_value: format!("{:.2}", od_val).to_string(),
             id_input_value: format!("{:.2}", id_val).to_string(),
+            current_od_input_value: String::new(),
+            width_input_value: String::new(),
+            basis_weight_input_value: String::new(),
+            price_input_value: String::new(),
+            price_basis: Some(0),
             valid_units: vec![units::CENTIMETERS, units::INCHES],
-            selected_unit: Some(0)
+            selected_unit: Some(0),
+            valid_thickness_units: vec![units::CENTIMETERS, units::INCHES, units::GAUGE, units::CALIPER_POINTS],
+            selected_thickness_unit: Some(0),
+            material_preset: Some(0),
+            valid_output_units: vec![units::YARDS, units::METERS, units::INCHES, units::CENTIMETERS],
+            selected_output_unit: Some(0),
+            auto_scale_output: true,
+            output_precision: Some(1),
+            number_locale: Some(0),
+            translator: translator,
+            themes: vec![UiTheme::classic()],
+            selected_theme: Some(0),
+            chart_swap_axes: false,
+            mode: AppMode::Estimate,
+            comparison_rows: vec![RollRowInput::new(&first_row_name)],
+            sort_column: SortColumn::Name,
+            sort_ascending: true,
+            job_by_pieces: false,
+            job_length_input_value: String::new(),
+            job_piece_count_input_value: String::new(),
+            job_cut_length_input_value: String::new(),
+            splice_allowance_input_value: "0.00".to_string(),
+            line_speed_input_value: String::new(),
+            splice_od_input_value: String::new(),
+            run_started: None,
+            focused: None,
+            nudge_step_input_value: "0.01".to_string(),
+            copy_status: None,
+            label_material_input_value: String::new(),
+            label_roll_id_input_value: String::new(),
+            label_target_input_value: "label.zpl".to_string(),
+            label_templates: vec![LabelTemplate::standard()],
+            selected_label_template: Some(0),
+            label_status: None,
+            report_path_input_value: "roll_report.pdf".to_string(),
+            report_status: None
+        }
+    }
+
+    /// Adds units loaded at runtime to the input, thickness and output unit lists, skipping any
+    /// that are already present.
+    pub fn add_units(&mut self, units: &[LengthUnit]) {
+        for unit in units {
+            if !self.valid_units.contains(unit) {
+                self.valid_units.push(unit.clone());
+            }
+            if !self.valid_thickness_units.contains(unit) {
+                self.valid_thickness_units.push(unit.clone());
+            }
+            if !self.valid_output_units.contains(unit) {
+                self.valid_output_units.push(unit.clone());
+            }
         }
     }
 
@@ -35,39 +310,540 @@ impl InputState {
         self.valid_units[self.selected_unit.unwrap_or(0)].clone()
     }
 
+    pub fn get_thickness_unit(&self) -> LengthUnit {
+        self.valid_thickness_units[self.selected_thickness_unit.unwrap_or(0)].clone()
+    }
+
+    pub fn get_material_preset(&self) -> MaterialPreset {
+        MATERIAL_PRESETS[self.material_preset.unwrap_or(0)]
+    }
+
+    /// Selects a material preset, switching the thickness field to the unit that material is
+    /// normally quoted in. A thickness already entered is converted, so it describes the same
+    /// material in the new unit.
+    pub fn select_material_preset(&mut self, idx: usize) {
+        self.material_preset = Some(idx);
+        if let Some(unit) = self.get_material_preset().thickness_unit() {
+            let from = self.get_thickness_unit();
+            self.selected_thickness_unit = self.valid_thickness_units.iter().position(|valid| *valid == unit)
+                .or(self.selected_thickness_unit);
+
+            let to = self.get_thickness_unit();
+            let locale = self.get_locale();
+            if from != to {
+                if let Some(thickness) = locale.parse_length(&self.thickness_input_value, from) {
+                    self.thickness_input_value = format_converted(thickness.value(&to), &locale);
+                }
+            }
+        }
+    }
+
     pub fn get_output_unit(&self) -> LengthUnit {
-        units::YARDS
+        self.valid_output_units[self.selected_output_unit.unwrap_or(0)].clone()
+    }
+
+    pub fn get_locale(&self) -> NumberLocale {
+        LOCALES[self.number_locale.unwrap_or(0)]
+    }
+
+    pub fn get_locale_strings(&self) -> Vec<String> {
+        LOCALES.iter().map(|locale| locale.name.to_string()).collect::<Vec<String>>()
+    }
+
+    /// Selects the interface language. Comparison rows that still have their default names are
+    /// renamed in the new language.
+    pub fn select_language(&mut self, idx: usize) {
+        let old_names = (0..self.comparison_rows.len())
+            .map(|row_idx| default_row_name(&self.translator, row_idx + 1))
+            .collect::<Vec<String>>();
+        self.translator.selected = Some(idx);
+
+        for (row_idx, row) in self.comparison_rows.iter_mut().enumerate() {
+            if row.name == old_names[row_idx] {
+                row.name = default_row_name(&self.translator, row_idx + 1);
+            }
+        }
+    }
+
+    /// Adds a comparison row named after its position in the table.
+    pub fn add_comparison_row(&mut self) {
+        let name = default_row_name(&self.translator, self.comparison_rows.len() + 1);
+        self.comparison_rows.push(RollRowInput::new(&name));
+    }
+
+    /// Selects the number locale, rewriting any numbers already entered so they still parse.
+    pub fn select_locale(&mut self, idx: usize) {
+        let from = self.get_locale();
+        self.number_locale = Some(idx);
+        let to = self.get_locale();
+
+        for input in vec![&mut self.thickness_input_value, &mut self.od_input_value, &mut self.id_input_value,
+                &mut self.current_od_input_value, &mut self.width_input_value, &mut self.basis_weight_input_value,
+                &mut self.price_input_value, &mut self.job_length_input_value, &mut self.job_cut_length_input_value,
+                &mut self.splice_allowance_input_value, &mut self.line_speed_input_value, &mut self.splice_od_input_value,
+                &mut self.nudge_step_input_value] {
+            relocalize(input, &from, &to);
+        }
+
+        for row in self.comparison_rows.iter_mut() {
+            for input in vec![&mut row.thickness, &mut row.od, &mut row.id, &mut row.width, &mut row.price_per_roll] {
+                relocalize(input, &from, &to);
+            }
+        }
+    }
+
+    /// Adds themes loaded at runtime, replacing any theme already loaded with the same code.
+    pub fn add_themes(&mut self, themes: Vec<UiTheme>) {
+        for theme in themes {
+            match self.themes.iter().position(|existing| existing.code == theme.code) {
+                Some(idx) => self.themes[idx] = theme,
+                None => self.themes.push(theme)
+            }
+        }
+    }
+
+    pub fn get_theme(&self) -> &UiTheme {
+        &self.themes[self.selected_theme.unwrap_or(0)]
+    }
+
+    /// Theme names in the selected language, or as given in the theme file for themes without
+    /// a translation.
+    pub fn get_theme_strings(&self) -> Vec<String> {
+        self.themes.iter().map(|theme| self.translator.tr_or(&format!("theme.{}", theme.code), &theme.name))
+            .collect::<Vec<String>>()
+    }
+
+    /// Formats a result for display. With auto scaling on, the result is shown in the most
+    /// readable unit of the output unit's system; custom units are always shown as selected.
+    pub fn format_output_length(&self, length: &Length) -> String {
+        let output_unit = self.get_output_unit();
+        match UnitSystem::of(&output_unit) {
+            Some(system) if self.auto_scale_output =>
+                DisplayFormat::new(system, self.get_output_precision()).format_localized(length, &self.get_locale()),
+            _ => self.get_locale().format_length(&length.convert_to(output_unit))
+        }
     }
 
     pub fn get_input_unit_strings(&self) -> Vec<String> {
-        self.valid_units.iter().map(|unit| unit.full_name().to_string()).collect::<Vec<String>>()
+        self.valid_units.iter().map(|unit| self.translator.unit_name(unit)).collect::<Vec<String>>()
     }
 
-    pub fn get_material_roll(&self) -> Option<estimator::MaterialRoll> {
-        let zero: Length = Length::zero();
-
-        let lengths: Option<(Length, Length, Length)> = units::parse_str(&self.thickness_input_value, self.get_input_unit())
-            .and_then(|thickness| { units::parse_str(&self.id_input_value, self.get_input_unit()).map(|id| { (thickness, id) }) })
-            .and_then(|(thickness, id)| { units::parse_str(&self.od_input_value, self.get_input_unit())
-                .map(|od| { (thickness, id, od) })
-            }).and_then(|(thickness, id, od)| {
-                if thickness > zero &&
-                        id > zero &&
-                        od > zero &&
-                        od > id {
-                    Some((thickness, id, od))
-                } else {
-                    None
-                }
-            });
+    pub fn get_thickness_unit_strings(&self) -> Vec<String> {
+        self.valid_thickness_units.iter().map(|unit| self.translator.unit_name(unit)).collect::<Vec<String>>()
+    }
+
+    pub fn get_material_preset_strings(&self) -> Vec<String> {
+        MATERIAL_PRESETS.iter().map(|preset| self.translator.tr(preset.message_key())).collect::<Vec<String>>()
+    }
+
+    pub fn get_output_unit_strings(&self) -> Vec<String> {
+        self.valid_output_units.iter().map(|unit| self.translator.unit_name(unit)).collect::<Vec<String>>()
+    }
+
+    pub fn get_output_precision(&self) -> Precision {
+        OUTPUT_PRECISIONS[self.output_precision.unwrap_or(0)]
+    }
+
+    pub fn get_output_precision_strings(&self) -> Vec<String> {
+        OUTPUT_PRECISIONS.iter().map(|precision| match *precision {
+            Precision::SignificantFigures(count) =>
+                self.translator.tr_args("precision.significant", &[("count", &count.to_string())]),
+            Precision::Decimals(count) =>
+                self.translator.tr_args("precision.decimals", &[("count", &count.to_string())])
+        }).collect::<Vec<String>>()
+    }
+
+    pub fn get_price_basis_strings(&self) -> Vec<String> {
+        PRICE_BASIS_MESSAGES.iter().map(|key| self.translator.tr(key)).collect::<Vec<String>>()
+    }
+
+    pub fn get_width(&self) -> Option<Length> {
+        self.get_locale().parse_length(&self.width_input_value, self.get_input_unit()).and_then(|width| {
+            if width > Length::zero() { Some(width) } else { None }
+        })
+    }
 
-        lengths.map(|(thickness, id, od)| {
-            estimator::MaterialRoll{
-                id: id,
-                od: od,
-                thickness: thickness
+    pub fn get_price(&self) -> Option<Price> {
+        self.get_locale().parse_number(&self.price_input_value).and_then(|value| {
+            Price::from_basis(self.price_basis.unwrap_or(0), value)
+        })
+    }
+
+    /// Basis weight, entered in grams per square meter, needed when the price is quoted per
+    /// kilogram.
+    pub fn get_basis_weight(&self) -> Option<BasisWeight> {
+        self.get_locale().parse_number(&self.basis_weight_input_value).and_then(|value| {
+            if value > 0.0 { Some(BasisWeight::new(value, GRAMS_PER_SQUARE_METER)) } else { None }
+        })
+    }
+
+    pub fn get_cost_estimate(&self) -> Option<CostEstimate> {
+        self.get_material_roll().and_then(|roll| {
+            self.get_width().and_then(|width| {
+                self.get_price().and_then(|price| {
+                    CostEstimate::new(&roll, &width, self.get_basis_weight(), price, self.get_output_unit())
+                })
+            })
+        })
+    }
+
+    /// Weight of the whole roll, from its length, width and basis weight.
+    pub fn get_roll_weight(&self) -> Option<Mass> {
+        self.get_material_roll().and_then(|roll| {
+            self.get_width().and_then(|width| {
+                self.get_basis_weight().map(|basis_weight| roll.get_weight(width, basis_weight))
+            })
+        })
+    }
+
+    /// The result as shown in the output label.
+    pub fn get_length_text(&self) -> Option<String> {
+        self.get_material_roll().map(|roll| self.format_output_length(&roll.get_roll_length()))
+    }
+
+    /// One line per input, with its unit, followed by the results, for pasting into emails and
+    /// other systems. Optional inputs and the weight are left out when not entered.
+    pub fn get_summary(&self) -> Option<String> {
+        let roll = match self.get_material_roll() {
+            Some(roll) => roll,
+            None => return None
+        };
+        let input_unit = self.get_input_unit();
+        let with_unit = |value: &str| format!("{} {}", value.trim(), input_unit.abbrev());
+        let inputs = SummaryInputs {
+            thickness: format!("{} {}", self.thickness_input_value.trim(), self.get_thickness_unit().abbrev()),
+            od: with_unit(&self.od_input_value),
+            id: with_unit(&self.id_input_value),
+            width: self.get_width().map(|_| with_unit(&self.width_input_value)),
+            basis_weight: self.get_basis_weight().map(|_| self.basis_weight_input_value.trim().to_string())
+        };
+
+        let length = self.format_output_length(&roll.get_roll_length());
+        let lines = report::summary_lines(&roll, &inputs, &length, self.get_roll_weight(), &self.get_locale(), &self.translator);
+        Some(lines.join("\n"))
+    }
+
+    pub fn get_copy_text(&self, content: CopyContent) -> Option<String> {
+        match content {
+            CopyContent::Length => self.get_length_text(),
+            CopyContent::Summary => self.get_summary()
+        }
+    }
+
+    /// Adds label templates loaded at runtime, replacing any template already loaded with the
+    /// same name.
+    pub fn add_label_templates(&mut self, templates: Vec<LabelTemplate>) {
+        for template in templates {
+            match self.label_templates.iter().position(|existing| existing.name == template.name) {
+                Some(idx) => self.label_templates[idx] = template,
+                None => self.label_templates.push(template)
+            }
+        }
+    }
+
+    pub fn get_label_template(&self) -> &LabelTemplate {
+        &self.label_templates[self.selected_label_template.unwrap_or(0)]
+    }
+
+    pub fn get_label_template_strings(&self) -> Vec<String> {
+        self.label_templates.iter().map(|template| template.name.clone()).collect::<Vec<String>>()
+    }
+
+    /// The roll entered in the single roll view, with the material name and roll id from the
+    /// label view. The length is printed in the output unit.
+    pub fn get_roll_label(&self) -> Option<RollLabel> {
+        self.get_material_roll().map(|roll| {
+            RollLabel {
+                material: self.label_material_input_value.trim().to_string(),
+                roll_id: self.label_roll_id_input_value.trim().to_string(),
+                roll: roll,
+                width: self.get_width(),
+                length_unit: self.get_output_unit(),
+                locale: self.get_locale()
+            }
+        })
+    }
+
+    pub fn get_label_zpl(&self) -> Option<String> {
+        self.get_roll_label().map(|label| label.render(self.get_label_template()))
+    }
+
+    pub fn get_print_target(&self) -> Option<PrintTarget> {
+        PrintTarget::parse(&self.label_target_input_value)
+    }
+
+    /// A report of the roll in the single roll view, headed by the material name and roll id
+    /// from the label view when they are entered. The chart uses the same units as on screen.
+    pub fn get_report(&self) -> Option<RollReport> {
+        let (roll, summary) = match (self.get_material_roll(), self.get_summary()) {
+            (Some(roll), Some(summary)) => (roll, summary),
+            _ => return None
+        };
+
+        let mut lines = Vec::new();
+        for &(label_key, value) in [("print.material", &self.label_material_input_value),
+                ("print.roll_id", &self.label_roll_id_input_value)].iter() {
+            if !value.trim().is_empty() {
+                lines.push(self.translator.tr_args("summary.line", &[("label", &self.translator.tr(label_key)), ("value", value.trim())]));
+            }
+        }
+        lines.extend(summary.lines().map(|line| line.to_string()));
+
+        Some(RollReport::new(roll, lines, self.get_input_unit(), self.get_output_unit(), self.get_locale(), &self.translator))
+    }
+
+    /// Returns the comparison table rows in display order, paired with their computed metrics.
+    /// Rows whose inputs are invalid are listed last, in their original order.
+    pub fn get_comparison_rows(&self) -> Vec<(usize, Option<RollMetrics>)> {
+        let unit = self.get_input_unit();
+        let output_unit = self.get_output_unit();
+        let locale = self.get_locale();
+        let metrics = self.comparison_rows.iter()
+            .map(|row| row.to_roll_format(unit.clone(), &locale).map(|format| RollMetrics::new(&format, output_unit.clone())))
+            .collect::<Vec<Option<RollMetrics>>>();
+
+        let valid = metrics.iter().enumerate()
+            .filter_map(|(idx, row)| row.clone().map(|metrics| (idx, metrics)))
+            .collect::<Vec<(usize, RollMetrics)>>();
+        let valid_metrics = valid.iter().map(|&(_, ref metrics)| metrics.clone()).collect::<Vec<RollMetrics>>();
+
+        let mut rows = comparison::sorted_indices(&valid_metrics, self.sort_column, self.sort_ascending).into_iter()
+            .map(|sorted_idx| (valid[sorted_idx].0, Some(valid[sorted_idx].1.clone())))
+            .collect::<Vec<(usize, Option<RollMetrics>)>>();
+
+        rows.extend(metrics.iter().enumerate().filter(|&(_, row)| row.is_none()).map(|(idx, _)| (idx, None)));
+        rows
+    }
+
+    /// Selects the column to sort the comparison table by. Selecting the current column again
+    /// reverses the direction.
+    pub fn sort_comparison_by(&mut self, column: SortColumn) {
+        if self.sort_column == column {
+            self.sort_ascending = !self.sort_ascending;
+        } else {
+            self.sort_column = column;
+            self.sort_ascending = true;
+        }
+    }
+
+    /// Job lengths are entered in the output unit, since that is the unit jobs are quoted in.
+    pub fn get_job_requirement(&self) -> Option<JobRequirement> {
+        let unit = self.get_output_unit();
+        if self.job_by_pieces {
+            let count_opt = self.job_piece_count_input_value.trim().parse::<u32>().ok();
+            count_opt.and_then(|count| {
+                self.get_locale().parse_length(&self.job_cut_length_input_value, unit).and_then(|cut_length| {
+                    if count > 0 && cut_length > Length::zero() {
+                        Some(JobRequirement::Pieces { count: count, cut_length: cut_length })
+                    } else {
+                        None
+                    }
+                })
+            })
+        } else {
+            self.get_locale().parse_length(&self.job_length_input_value, unit).and_then(|length| {
+                if length > Length::zero() { Some(JobRequirement::TotalLength(length)) } else { None }
+            })
+        }
+    }
+
+    pub fn get_job_plan(&self) -> Option<JobPlan> {
+        let splice_allowance = self.get_locale().parse_length(&self.splice_allowance_input_value, self.get_output_unit())
+            .unwrap_or_else(Length::zero);
+
+        self.get_material_roll().and_then(|roll| {
+            self.get_job_requirement().and_then(|requirement| {
+                planner::plan_job(&roll, &requirement, &splice_allowance)
+            })
+        })
+    }
+
+    /// Line speed is entered in the output unit per minute.
+    pub fn get_line_speed(&self) -> Option<Speed> {
+        self.get_locale().parse_number(&self.line_speed_input_value).and_then(|value| {
+            if value > 0.0 {
+                Some(Length::new(value, self.get_output_unit()) / Time::new(1.0, MINUTES))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn get_splice_od(&self) -> Option<Length> {
+        self.get_locale().parse_length(&self.splice_od_input_value, self.get_input_unit())
+    }
+
+    pub fn get_time_to_empty(&self) -> Option<Duration> {
+        self.get_material_roll().and_then(|roll| {
+            self.get_line_speed().and_then(|speed| {
+                runtime::time_to_empty(&roll, self.get_current_od().as_ref(), &speed)
+            })
+        })
+    }
+
+    pub fn get_time_to_splice(&self) -> Option<Duration> {
+        self.get_material_roll().and_then(|roll| {
+            self.get_line_speed().and_then(|speed| {
+                self.get_splice_od().and_then(|splice_od| {
+                    runtime::time_to_diameter(&roll, self.get_current_od().as_ref(), &splice_od, &speed)
+                })
+            })
+        })
+    }
+
+    /// Counts an estimate down by the time elapsed since the run was started, stopping at zero.
+    pub fn count_down(&self, estimate: Duration) -> Duration {
+        match self.run_started {
+            Some(started) => {
+                let elapsed = started.elapsed();
+                if elapsed >= estimate { Duration::new(0, 0) } else { estimate - elapsed }
             }
+            None => estimate
+        }
+    }
+
+    pub fn get_current_od(&self) -> Option<Length> {
+        self.get_material_roll().and_then(|roll| {
+            self.get_locale().parse_length(&self.current_od_input_value, self.get_input_unit())
+                .and_then(|current_od| {
+                    if current_od > roll.id && current_od <= roll.od {
+                        Some(current_od)
+                    } else {
+                        None
+                    }
+                })
+        })
+    }
+
+    /// The amount the arrow keys change the focused value by, in the unit of that value.
+    pub fn get_nudge_step(&self) -> Option<f64> {
+        self.get_locale().parse_number(&self.nudge_step_input_value).and_then(|step| {
+            if step > 0.0 { Some(step) } else { None }
         })
     }
 
+    /// Applies a navigation key. Tab and Shift+Tab move through `FOCUS_ORDER`, wrapping at
+    /// either end, and Enter moves to the next control like Tab; results are recalculated from
+    /// the inputs every frame, so there is nothing to confirm. Up and Down nudge the focused
+    /// value by the nudge step, or select the previous or next unit when a unit list is focused.
+    pub fn handle_key(&mut self, key: NavKey) {
+        let position = self.focused.and_then(|focused| FOCUS_ORDER.iter().position(|target| *target == focused));
+        match key {
+            NavKey::Tab | NavKey::Enter => {
+                let next = position.map(|idx| (idx + 1) % FOCUS_ORDER.len()).unwrap_or(0);
+                self.focused = Some(FOCUS_ORDER[next]);
+            }
+            NavKey::BackTab => {
+                let previous = position.map(|idx| (idx + FOCUS_ORDER.len() - 1) % FOCUS_ORDER.len())
+                    .unwrap_or(FOCUS_ORDER.len() - 1);
+                self.focused = Some(FOCUS_ORDER[previous]);
+            }
+            NavKey::Up => self.nudge_focused(1),
+            NavKey::Down => self.nudge_focused(-1)
+        }
+    }
+
+    fn nudge_focused(&mut self, direction: i32) {
+        let focused = match self.focused {
+            Some(focused) => focused,
+            None => return
+        };
+
+        let unit_list = match focused {
+            FocusTarget::ThicknessUnit => Some((&mut self.selected_thickness_unit, self.valid_thickness_units.len())),
+            FocusTarget::DiameterUnit => Some((&mut self.selected_unit, self.valid_units.len())),
+            FocusTarget::OutputUnit => Some((&mut self.selected_output_unit, self.valid_output_units.len())),
+            _ => None
+        };
+        if let Some((selected, count)) = unit_list {
+            // The lists are drawn top to bottom, so Up selects the previous entry
+            let current = selected.unwrap_or(0) as i32;
+            *selected = Some((current - direction).max(0).min(count as i32 - 1) as usize);
+            return;
+        }
+
+        let step = match self.get_nudge_step() {
+            Some(step) => step,
+            None => return
+        };
+        let locale = self.get_locale();
+        let step_input = self.nudge_step_input_value.trim().to_string();
+        let decimals = step_input.rfind(locale.decimal_separator)
+            .map(|idx| step_input.len() - idx - 1)
+            .unwrap_or(0)
+            .max(2);
+
+        let input = match focused {
+            FocusTarget::Thickness => &mut self.thickness_input_value,
+            FocusTarget::Od => &mut self.od_input_value,
+            FocusTarget::Id => &mut self.id_input_value,
+            FocusTarget::CurrentOd => &mut self.current_od_input_value,
+            FocusTarget::Width => &mut self.width_input_value,
+            FocusTarget::BasisWeight => &mut self.basis_weight_input_value,
+            FocusTarget::Price => &mut self.price_input_value,
+            FocusTarget::ThicknessUnit | FocusTarget::DiameterUnit | FocusTarget::OutputUnit => return
+        };
+        let value = locale.parse_number(input).unwrap_or(0.0) + step * direction as f64;
+        *input = locale.format_number(value.max(0.0), decimals);
+    }
+
+    pub fn get_material_roll(&self) -> Option<estimator::MaterialRoll> {
+        parse_material_roll(&self.thickness_input_value, &self.id_input_value, &self.od_input_value,
+                self.get_thickness_unit(), self.get_input_unit(), &self.get_locale())
+    }
+
+}
+
+fn default_row_name(translator: &Translator, number: usize) -> String {
+    translator.tr_args("table.roll_name", &[("number", &number.to_string())])
+}
+
+/// Rewrites a number typed in one locale for another, keeping as many decimals as were typed.
+fn relocalize(input: &mut String, from: &NumberLocale, to: &NumberLocale) {
+    if let Some(value) = from.parse_number(input) {
+        let decimals = input.trim().splitn(2, from.decimal_separator).nth(1).map(|fraction| fraction.len()).unwrap_or(0);
+        *input = to.format_number(value, decimals);
+    }
+}
+
+/// Formats a value converted between units to five significant digits, without trailing zeros,
+/// so that neither a small thickness nor a large one loses its precision.
+fn format_converted(value: f64, locale: &NumberLocale) -> String {
+    let magnitude = if value == 0.0 { 0 } else { value.abs().log10().floor() as i32 };
+    let decimals = (4 - magnitude).max(0) as usize;
+    let formatted = format!("{:.*}", decimals, value);
+    let trimmed = if formatted.contains('.') {
+        formatted.trim_right_matches('0').trim_right_matches('.')
+    } else {
+        &formatted
+    };
+    locale.localize(trimmed)
+}
+
+fn parse_material_roll(thickness_input: &str, id_input: &str, od_input: &str, thickness_unit: LengthUnit,
+        unit: LengthUnit, locale: &NumberLocale) -> Option<estimator::MaterialRoll> {
+    let zero: Length = Length::zero();
+
+    let lengths: Option<(Length, Length, Length)> = locale.parse_length(thickness_input, thickness_unit)
+        .and_then(|thickness| { locale.parse_length(id_input, unit.clone()).map(|id| { (thickness, id) }) })
+        .and_then(|(thickness, id)| { locale.parse_length(od_input, unit.clone())
+            .map(|od| { (thickness, id, od) })
+        }).and_then(|(thickness, id, od)| {
+            if thickness > zero &&
+                    id > zero &&
+                    od > zero &&
+                    od > id {
+                Some((thickness, id, od))
+            } else {
+                None
+            }
+        });
+
+    lengths.map(|(thickness, id, od)| {
+        estimator::MaterialRoll{
+            id: id,
+            od: od,
+            thickness: thickness
+        }
+    })
 }
@@ -1,6 +1,15 @@
 
-use ::estimator::units::{INCHES, Length};
-use super::InputState;
+use ::estimator::units::{CENTIMETERS, GAUGE, INCHES, METERS, Length, LengthUnit};
+use ::estimator::comparison::SortColumn;
+use ::estimator::locale::{ENGLISH, LOCALES, FRENCH, GERMAN};
+use ::estimator::cost::Price;
+use ::estimator::quantities::KILOGRAMS;
+use std::time::Duration;
+use super::{InputState, RollRowInput, MaterialPreset, MATERIAL_PRESETS, OUTPUT_PRECISIONS, FocusTarget, NavKey, CopyContent};
+use ::estimator::display::Precision;
+use ::i18n::Catalog;
+use ::gui::theme::UiTheme;
+use ::label::{LabelTemplate, PrintTarget};
 
 
 #[test]
@@ -78,3 +87,385 @@ fn app_state_get_material_roll_should_return_none_if_od_is_not_greater_than_id()
 
     assert!(roll_option.is_none());
 }
+
+#[test]
+fn app_state_get_current_od_should_return_none_if_input_is_empty() {
+    let app_state = InputState::new();
+    assert!(app_state.get_current_od().is_none());
+}
+
+#[test]
+fn app_state_get_current_od_should_return_parsed_length_between_id_and_od() {
+    let mut app_state = InputState::new();
+    app_state.current_od_input_value = "8.0".to_string();
+    let expected = Length::new(8.0, app_state.get_input_unit());
+    assert_eq!(Some(expected), app_state.get_current_od());
+}
+
+#[test]
+fn app_state_get_current_od_should_return_none_if_greater_than_od() {
+    let mut app_state = InputState::new();
+    app_state.current_od_input_value = "13.0".to_string();
+    assert!(app_state.get_current_od().is_none());
+}
+
+#[test]
+fn app_state_comparison_rows_should_be_sorted_with_invalid_rows_last() {
+    let mut app_state = InputState::new();
+    let mut invalid = RollRowInput::new("Invalid");
+    invalid.od = "j/k".to_string();
+    let mut large = RollRowInput::new("Large");
+    large.od = "20.0".to_string();
+    app_state.comparison_rows = vec![invalid, RollRowInput::new("Small"), large];
+    app_state.sort_comparison_by(SortColumn::Length);
+    app_state.sort_comparison_by(SortColumn::Length);
+
+    let order = app_state.get_comparison_rows().iter().map(|&(idx, _)| idx).collect::<Vec<usize>>();
+    assert_eq!(vec![2, 1, 0], order);
+}
+
+#[test]
+fn app_state_comparison_rows_should_sort_by_input_columns() {
+    let mut app_state = InputState::new();
+    let mut thick = RollRowInput::new("Thick");
+    thick.thickness = "0.12".to_string();
+    let mut narrow = RollRowInput::new("Narrow");
+    narrow.width = "40".to_string();
+    app_state.comparison_rows = vec![thick, narrow];
+
+    app_state.sort_comparison_by(SortColumn::Thickness);
+    let order = app_state.get_comparison_rows().iter().map(|&(idx, _)| idx).collect::<Vec<usize>>();
+    assert_eq!(vec![1, 0], order);
+
+    app_state.sort_comparison_by(SortColumn::Width);
+    let order = app_state.get_comparison_rows().iter().map(|&(idx, _)| idx).collect::<Vec<usize>>();
+    assert_eq!(vec![1, 0], order);
+}
+
+#[test]
+fn roll_row_input_should_be_invalid_if_price_is_not_a_number() {
+    let mut row = RollRowInput::new("Roll");
+    row.price_per_roll = "abc".to_string();
+    assert!(row.to_roll_format(INCHES, &ENGLISH).is_none());
+
+    row.price_per_roll = "42.50".to_string();
+    assert_eq!(Some(Price::PerRoll(42.5)), row.to_roll_format(INCHES, &ENGLISH).unwrap().price);
+}
+
+#[test]
+fn app_state_get_cost_estimate_should_return_none_without_width_or_price() {
+    let mut app_state = InputState::new();
+    assert!(app_state.get_cost_estimate().is_none());
+
+    app_state.width_input_value = "100".to_string();
+    assert!(app_state.get_cost_estimate().is_none());
+
+    app_state.price_input_value = "25.00".to_string();
+    assert!(app_state.get_cost_estimate().is_some());
+}
+
+#[test]
+fn app_state_cost_estimate_should_follow_selected_output_unit() {
+    let mut app_state = InputState::new();
+    app_state.width_input_value = "100".to_string();
+    app_state.price_input_value = "25.00".to_string();
+    app_state.selected_output_unit = Some(1);
+
+    let estimate = app_state.get_cost_estimate().unwrap();
+    assert_eq!(METERS, estimate.unit);
+}
+
+#[test]
+fn app_state_get_job_plan_should_use_length_or_pieces_inputs() {
+    let mut app_state = InputState::new();
+    assert!(app_state.get_job_plan().is_none());
+
+    app_state.job_length_input_value = "500".to_string();
+    assert_eq!(None, app_state.get_job_plan().unwrap().pieces_per_roll);
+
+    app_state.job_by_pieces = true;
+    assert!(app_state.get_job_plan().is_none());
+
+    app_state.job_piece_count_input_value = "10".to_string();
+    app_state.job_cut_length_input_value = "2".to_string();
+    assert!(app_state.get_job_plan().unwrap().pieces_per_roll.is_some());
+}
+
+#[test]
+fn app_state_time_to_splice_should_be_less_than_time_to_empty() {
+    let mut app_state = InputState::new();
+    app_state.line_speed_input_value = "10".to_string();
+    app_state.splice_od_input_value = "6".to_string();
+
+    let to_empty = app_state.get_time_to_empty().unwrap();
+    let to_splice = app_state.get_time_to_splice().unwrap();
+    assert!(to_splice < to_empty);
+}
+
+#[test]
+fn app_state_count_down_should_not_change_estimate_until_run_is_started() {
+    let app_state = InputState::new();
+    let estimate = Duration::new(90, 0);
+    assert_eq!(estimate, app_state.count_down(estimate));
+}
+
+#[test]
+fn add_units_should_append_new_units_to_input_and_output_lists() {
+    let mut app_state = InputState::new();
+    let picas = LengthUnit::new("Picas", "pc", 0.0042333);
+    app_state.add_units(&[picas.clone(), INCHES]);
+
+    assert_eq!(3, app_state.valid_units.len());
+    assert_eq!(5, app_state.valid_thickness_units.len());
+    assert_eq!(5, app_state.valid_output_units.len());
+    assert_eq!("Picas", app_state.get_input_unit_strings()[2]);
+    assert_eq!(Some(&picas), app_state.valid_output_units.last());
+}
+
+#[test]
+fn choosing_film_preset_should_switch_thickness_to_gauge() {
+    let mut app_state = InputState::new();
+    let film_idx = MATERIAL_PRESETS.iter().position(|preset| *preset == MaterialPreset::Film).unwrap();
+    app_state.select_material_preset(film_idx);
+    app_state.thickness_input_value = "100".to_string();
+
+    assert_eq!(GAUGE, app_state.get_thickness_unit());
+    let roll = app_state.get_material_roll().unwrap();
+    assert_eq!(Length::new(100.0, GAUGE), roll.thickness);
+    assert_eq!(CENTIMETERS, roll.od.unit);
+}
+
+#[test]
+fn choosing_film_preset_should_convert_entered_thickness() {
+    let mut app_state = InputState::new();
+    app_state.thickness_input_value = "0,08".to_string();
+    app_state.number_locale = LOCALES.iter().position(|locale| *locale == GERMAN);
+    let film_idx = MATERIAL_PRESETS.iter().position(|preset| *preset == MaterialPreset::Film).unwrap();
+    app_state.select_material_preset(film_idx);
+
+    assert_eq!("3.149,6", app_state.thickness_input_value);
+}
+
+#[test]
+fn choosing_fabric_preset_should_keep_thickness_unit() {
+    let mut app_state = InputState::new();
+    app_state.selected_thickness_unit = Some(1);
+    app_state.select_material_preset(1);
+
+    assert_eq!(MaterialPreset::Fabric, app_state.get_material_preset());
+    assert_eq!(INCHES, app_state.get_thickness_unit());
+}
+
+#[test]
+fn output_length_should_be_scaled_within_output_unit_system() {
+    let mut app_state = InputState::new();
+    app_state.selected_output_unit = Some(1);
+    let length = Length::new(123456.78, CENTIMETERS);
+
+    assert_eq!("1.235 km", app_state.format_output_length(&length));
+
+    app_state.output_precision = OUTPUT_PRECISIONS.iter().position(|precision| *precision == Precision::Decimals(2));
+    assert_eq!("1.23 km", app_state.format_output_length(&length));
+    assert_eq!("2 decimal places", app_state.get_output_precision_strings()[3]);
+
+    app_state.auto_scale_output = false;
+    assert_eq!("1,234.57 m", app_state.format_output_length(&length));
+}
+
+#[test]
+fn changing_locale_should_keep_typed_decimals() {
+    let mut app_state = InputState::new();
+    app_state.od_input_value = "1234.50".to_string();
+    app_state.width_input_value = "150".to_string();
+    app_state.select_locale(LOCALES.iter().position(|locale| *locale == FRENCH).unwrap());
+
+    assert_eq!("1 234,50", app_state.od_input_value);
+    assert_eq!("150", app_state.width_input_value);
+    assert_eq!(Some(1234.5), FRENCH.parse_number(&app_state.od_input_value));
+}
+
+#[test]
+fn changing_locale_should_rewrite_nudge_step() {
+    let mut app_state = InputState::new();
+    app_state.select_locale(LOCALES.iter().position(|locale| *locale == FRENCH).unwrap());
+    assert_eq!("0,01", app_state.nudge_step_input_value);
+}
+
+#[test]
+fn inputs_should_be_parsed_and_results_formatted_in_selected_locale() {
+    let mut app_state = InputState::new();
+    app_state.select_locale(LOCALES.iter().position(|locale| *locale == GERMAN).unwrap());
+    assert_eq!("4,00", app_state.id_input_value);
+    app_state.thickness_input_value = "0,08".to_string();
+    app_state.od_input_value = "1.200,5".to_string();
+    app_state.auto_scale_output = false;
+    app_state.selected_output_unit = Some(1);
+
+    let roll = app_state.get_material_roll().unwrap();
+    assert_eq!(Length::new(0.08, CENTIMETERS), roll.thickness);
+    assert_eq!(Length::new(1200.5, CENTIMETERS), roll.od);
+    assert_eq!("1.234,57 m", app_state.format_output_length(&Length::new(1234.567, METERS)));
+}
+
+#[test]
+fn unit_and_preset_names_should_follow_selected_language() {
+    let mut app_state = InputState::new();
+    assert_eq!(vec!["Centimeters".to_string(), "Inches".to_string()], app_state.get_input_unit_strings());
+
+    app_state.translator.add(Catalog::parse("es", "unit.cm = Centímetros\npreset.film = Película plástica").unwrap());
+    app_state.translator.select("es");
+    assert_eq!(vec!["Centímetros".to_string(), "Inches".to_string()], app_state.get_input_unit_strings());
+    assert_eq!("Película plástica", app_state.get_material_preset_strings()[2]);
+    assert_eq!("Per Roll", app_state.get_price_basis_strings()[0]);
+}
+
+#[test]
+fn tab_should_cycle_through_controls_in_both_directions() {
+    let mut app_state = InputState::new();
+    app_state.handle_key(NavKey::Tab);
+    assert_eq!(Some(FocusTarget::Thickness), app_state.focused);
+    app_state.handle_key(NavKey::Tab);
+    app_state.handle_key(NavKey::Enter);
+    assert_eq!(Some(FocusTarget::Od), app_state.focused);
+
+    app_state.handle_key(NavKey::BackTab);
+    app_state.handle_key(NavKey::BackTab);
+    app_state.handle_key(NavKey::BackTab);
+    assert_eq!(Some(FocusTarget::OutputUnit), app_state.focused);
+    app_state.handle_key(NavKey::Tab);
+    assert_eq!(Some(FocusTarget::Thickness), app_state.focused);
+}
+
+#[test]
+fn arrow_keys_should_nudge_focused_value_by_step() {
+    let mut app_state = InputState::new();
+    app_state.focused = Some(FocusTarget::Od);
+    app_state.nudge_step_input_value = "0.5".to_string();
+    app_state.handle_key(NavKey::Up);
+    assert_eq!("12.50", app_state.od_input_value);
+
+    app_state.focused = Some(FocusTarget::Thickness);
+    app_state.nudge_step_input_value = "0.005".to_string();
+    app_state.handle_key(NavKey::Down);
+    assert_eq!("0.075", app_state.thickness_input_value);
+}
+
+#[test]
+fn arrow_keys_should_move_through_focused_unit_list() {
+    let mut app_state = InputState::new();
+    app_state.focused = Some(FocusTarget::OutputUnit);
+    app_state.handle_key(NavKey::Down);
+    app_state.handle_key(NavKey::Down);
+    assert_eq!(Some(2), app_state.selected_output_unit);
+    app_state.handle_key(NavKey::Up);
+    app_state.handle_key(NavKey::Up);
+    app_state.handle_key(NavKey::Up);
+    assert_eq!(Some(0), app_state.selected_output_unit);
+}
+
+#[test]
+fn loaded_themes_should_replace_same_code_and_use_translated_names() {
+    let mut app_state = InputState::new();
+    app_state.add_themes(vec![
+        UiTheme::parse("ocean", "name = Ocean").unwrap(),
+        UiTheme::parse("dark", "name = Dark").unwrap(),
+        UiTheme::parse("ocean", "name = Deep Ocean").unwrap()
+    ]);
+    assert_eq!(vec!["Classic", "Deep Ocean", "Dark"], app_state.get_theme_strings());
+
+    app_state.translator.add(Catalog::parse("de", "theme.dark = Dunkel").unwrap());
+    app_state.translator.select("de");
+    app_state.selected_theme = Some(2);
+    assert_eq!("Dunkel", app_state.get_theme_strings()[2]);
+    assert_eq!("dark", app_state.get_theme().code);
+}
+
+#[test]
+fn summary_should_list_inputs_with_units_and_results() {
+    let mut app_state = InputState::new();
+    assert_eq!(3 + 2, app_state.get_summary().unwrap().lines().count());
+
+    app_state.width_input_value = "100".to_string();
+    app_state.basis_weight_input_value = "80".to_string();
+    let summary = app_state.get_summary().unwrap();
+    let lines = summary.lines().collect::<Vec<&str>>();
+    assert_eq!("Material Thickness: 0.08 cm", lines[0]);
+    assert_eq!("Material Width: 100 cm", lines[3]);
+    assert_eq!("Basis Weight (g/m²): 80", lines[4]);
+    assert_eq!(format!("Total Length: {}", app_state.get_length_text().unwrap()), lines[5]);
+    assert_eq!("Layers: 100", lines[6]);
+
+    let length_m = app_state.get_material_roll().unwrap().get_roll_length().value(&METERS);
+    let weight = app_state.get_roll_weight().unwrap().value(&KILOGRAMS);
+    assert!((weight - length_m * 0.08).abs() < 0.0001);
+    assert_eq!(format!("Weight: {:.2} kg", weight), lines[7]);
+}
+
+#[test]
+fn copy_text_should_be_unavailable_without_a_valid_roll() {
+    let mut app_state = InputState::new();
+    assert_eq!(app_state.get_length_text(), app_state.get_copy_text(CopyContent::Length));
+    app_state.od_input_value = "1".to_string();
+    assert_eq!(None, app_state.get_copy_text(CopyContent::Length));
+    assert_eq!(None, app_state.get_copy_text(CopyContent::Summary));
+}
+
+#[test]
+fn roll_label_should_use_entered_roll_and_selected_template() {
+    let mut app_state = InputState::new();
+    app_state.label_material_input_value = " Canvas ".to_string();
+    app_state.label_roll_id_input_value = "R-7".to_string();
+    app_state.width_input_value = "150".to_string();
+    app_state.add_label_templates(vec![
+        LabelTemplate::parse("brief", "{material} {roll_id} {width}").unwrap(),
+        LabelTemplate::parse("standard", "{layers}").unwrap()
+    ]);
+    assert_eq!(vec!["standard", "brief"], app_state.get_label_template_strings());
+    assert_eq!(Some("100".to_string()), app_state.get_label_zpl());
+
+    app_state.selected_label_template = Some(1);
+    assert_eq!(Some("Canvas R-7 150.00 cm".to_string()), app_state.get_label_zpl());
+
+    app_state.selected_output_unit = Some(1);
+    assert_eq!(METERS, app_state.get_roll_label().unwrap().length_unit);
+
+    app_state.label_target_input_value = "tcp://zebra".to_string();
+    assert_eq!(Some(PrintTarget::Tcp("zebra:9100".to_string())), app_state.get_print_target());
+
+    app_state.thickness_input_value = "0".to_string();
+    assert_eq!(None, app_state.get_label_zpl());
+}
+
+#[test]
+fn report_should_add_label_details_to_summary() {
+    let mut app_state = InputState::new();
+    let summary_lines = app_state.get_summary().unwrap().lines().count();
+    let report = app_state.get_report().unwrap();
+    assert_eq!("Roll Report", report.title);
+    assert_eq!(summary_lines, report.lines.len());
+    assert_eq!("Diameter (cm)", report.diameter_label);
+    assert_eq!("Length (yrd)", report.length_label);
+
+    app_state.label_roll_id_input_value = "R-7".to_string();
+    let report = app_state.get_report().unwrap();
+    assert_eq!("Roll ID: R-7", report.lines[0]);
+    assert_eq!(summary_lines + 1, report.lines.len());
+
+    app_state.id_input_value = String::new();
+    assert!(app_state.get_report().is_none());
+}
+
+#[test]
+fn default_row_names_should_follow_selected_language() {
+    let mut app_state = InputState::new();
+    app_state.translator.add(Catalog::parse("de", "table.roll_name = Rolle {number}").unwrap());
+    app_state.add_comparison_row();
+    app_state.comparison_rows[1].name = "Supplier B".to_string();
+    assert_eq!("Roll 1", app_state.comparison_rows[0].name);
+
+    app_state.select_language(1);
+    assert_eq!("Rolle 1", app_state.comparison_rows[0].name);
+    assert_eq!("Supplier B", app_state.comparison_rows[1].name);
+    app_state.add_comparison_row();
+    assert_eq!("Rolle 3", app_state.comparison_rows[2].name);
+}
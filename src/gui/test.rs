@@ -1,4 +1,18 @@
-use super::fix_numeric_str;
+use super::{fix_numeric_str, Gui};
+use super::headless::HeadlessGui;
+use super::layout::Layout;
+use super::raster::Canvas;
+use super::state::{AppMode, FocusTarget, InputState, APP_MODES};
+use super::label_panel::{print_label, save_report};
+use super::theme::UiTheme;
+use super::input::Key;
+use ::estimator::units::UnitRegistry;
+
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process;
 
 #[test]
 fn simple_integer_should_be_unchanged() {
@@ -20,3 +34,177 @@ fn number_with_letter_should_be_truncated_so_only_numeric_chars_remain() {
     fix_numeric_str(&mut number);
     assert_eq!("123".to_string(), *number);
 }
+
+#[test]
+fn decimal_comma_should_be_kept_for_locale_aware_parsing() {
+    let mut number = &mut "1.234,56".to_string();
+    fix_numeric_str(&mut number);
+    assert_eq!("1.234,56".to_string(), *number);
+}
+
+#[test]
+fn space_thousands_separators_should_be_kept_for_locale_aware_parsing() {
+    let mut number = &mut "1 234\u{a0}567,8 cm".to_string();
+    fix_numeric_str(&mut number);
+    assert_eq!("1 234\u{a0}567,8 ".to_string(), *number);
+}
+
+fn headless_gui() -> HeadlessGui {
+    let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+    HeadlessGui::new(Gui::new(&UnitRegistry::new(), &assets, 1.0), 1100, 520)
+}
+
+/// Compares a rendered frame with `tests/screenshots/<name>.ppm`. When `UPDATE_SCREENSHOTS`
+/// is set the reference is written instead; review and commit the new images. A missing
+/// reference fails, as does a mismatch, and the frame is saved as `<name>.actual.ppm`.
+fn assert_screenshot(name: &str, canvas: &Canvas) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/screenshots");
+    let reference_path = dir.join(format!("{}.ppm", name));
+    let actual_path = dir.join(format!("{}.actual.ppm", name));
+
+    if env::var("UPDATE_SCREENSHOTS").is_ok() {
+        fs::create_dir_all(&dir).unwrap();
+        canvas.save(&reference_path).unwrap();
+        return;
+    }
+
+    let reference = match Canvas::load(&reference_path) {
+        Some(reference) => reference,
+        None => {
+            fs::create_dir_all(&dir).unwrap();
+            canvas.save(&actual_path).unwrap();
+            panic!("{} has no reference screenshot, run with UPDATE_SCREENSHOTS=1 to create {} from {}",
+                name, reference_path.display(), actual_path.display());
+        }
+    };
+
+    let difference = canvas.difference(&reference, 2);
+    if difference.map(|fraction| fraction > 0.001).unwrap_or(true) {
+        canvas.save(&actual_path).unwrap();
+        panic!("{} differs from its reference screenshot by {:?}, see {}", name, difference, actual_path.display());
+    }
+}
+
+#[test]
+fn tab_and_arrow_keys_should_nudge_thickness() {
+    let mut headless = headless_gui();
+    headless.frame();
+    headless.press_key(Key::Tab);
+    headless.press_key(Key::Up);
+    assert_eq!(Some(FocusTarget::Thickness), headless.gui.app_state.focused);
+    assert_eq!("0.09", headless.gui.app_state.thickness_input_value);
+}
+
+#[test]
+fn clicking_should_clear_keyboard_focus() {
+    let mut headless = headless_gui();
+    headless.frame();
+    headless.press_key(Key::Tab);
+    assert_eq!(Some(FocusTarget::Thickness), headless.gui.app_state.focused);
+
+    let position = headless.to_window([0.0, 0.0]);
+    headless.click(position);
+    assert_eq!(None, headless.gui.app_state.focused);
+    headless.press_key(Key::Up);
+    assert_eq!("0.08", headless.gui.app_state.thickness_input_value);
+}
+
+#[test]
+fn clicking_mode_button_should_switch_mode() {
+    let mut headless = headless_gui();
+    headless.frame();
+
+    // Mode buttons are placed from the right of the header, last mode first
+    let layout = Layout::new(1100.0, 520.0, 1.0);
+    let compare_idx = APP_MODES.iter().position(|mode| *mode == AppMode::Compare).unwrap();
+    let button = layout.header_controls[APP_MODES.len() - 1 - compare_idx].center();
+    let position = headless.to_window(button);
+    headless.click(position);
+    assert_eq!(AppMode::Compare, headless.gui.app_state.mode);
+}
+
+#[test]
+fn clicking_mode_button_should_switch_mode_in_narrow_window() {
+    let mut headless = HeadlessGui::new(headless_gui().gui, 500, 900);
+    headless.frame();
+
+    let layout = Layout::new(500.0, 900.0, 1.0);
+    let label_idx = APP_MODES.iter().position(|mode| *mode == AppMode::Label).unwrap();
+    let button = layout.header_controls[APP_MODES.len() - 1 - label_idx].center();
+    let position = headless.to_window(button);
+    headless.click(position);
+    assert_eq!(AppMode::Label, headless.gui.app_state.mode);
+}
+
+#[test]
+fn rendered_background_should_follow_selected_theme() {
+    let mut headless = headless_gui();
+    let corner = |canvas: &Canvas| canvas.pixel(0, canvas.height - 1);
+
+    let mut expected = Canvas::new(1, 1);
+    expected.clear(UiTheme::classic().background);
+    assert_eq!(expected.pixel(0, 0), corner(&headless.render()));
+
+    let high_contrast = headless.gui.app_state.themes.iter().position(|theme| theme.code == "high_contrast");
+    headless.gui.app_state.selected_theme = high_contrast;
+    assert_eq!([0, 0, 0], corner(&headless.render()));
+}
+
+#[test]
+fn estimate_view_should_match_reference_screenshots() {
+    let mut headless = headless_gui();
+    assert_screenshot("estimate_classic", &headless.render());
+
+    let high_contrast = headless.gui.app_state.themes.iter().position(|theme| theme.code == "high_contrast");
+    headless.gui.app_state.selected_theme = high_contrast;
+    assert_screenshot("estimate_high_contrast", &headless.render());
+}
+
+#[test]
+fn compare_view_should_match_reference_screenshot() {
+    let mut headless = headless_gui();
+    headless.gui.app_state.mode = AppMode::Compare;
+    assert_screenshot("compare_classic", &headless.render());
+}
+
+#[test]
+fn print_label_should_write_zpl_and_report_target() {
+    let mut app_state = InputState::new();
+    let path = env::temp_dir().join(format!("fabric_roll_gui_label_test_{}.zpl", process::id()));
+    app_state.label_target_input_value = path.to_string_lossy().into_owned();
+    print_label(&mut app_state);
+
+    let written = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+    fs::remove_file(&path).unwrap();
+    assert!(written > 0);
+    assert_eq!(Some(format!("Label sent to {}", path.display())), app_state.label_status);
+
+    app_state.label_target_input_value = String::new();
+    print_label(&mut app_state);
+    assert_eq!(Some("Enter a file or tcp://printer to print to".to_string()), app_state.label_status);
+}
+
+#[test]
+fn save_report_should_write_pdf_and_report_path() {
+    let mut app_state = InputState::new();
+    let path = env::temp_dir().join(format!("fabric_roll_gui_report_test_{}.pdf", process::id()));
+    app_state.report_path_input_value = path.to_string_lossy().into_owned();
+    save_report(&mut app_state);
+
+    let mut header = [0u8; 5];
+    fs::File::open(&path).and_then(|mut file| file.read_exact(&mut header)).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(b"%PDF-", &header);
+    assert_eq!(Some(format!("Report saved to {}", path.display())), app_state.report_status);
+
+    app_state.od_input_value = "1".to_string();
+    save_report(&mut app_state);
+    assert_eq!(Some("Enter a valid roll in the Single Roll view".to_string()), app_state.report_status);
+}
+
+#[test]
+fn label_view_should_match_reference_screenshot() {
+    let mut headless = headless_gui();
+    headless.gui.app_state.mode = AppMode::Label;
+    assert_screenshot("label_classic", &headless.render());
+}
@@ -0,0 +1,246 @@
+//! Colour schemes and fonts for the GUI, read from theme files where each line reads
+//! `key = value`. Colours are written `#RRGGBB` or `#RRGGBBAA`, and any key a file leaves out
+//! keeps its value from the classic theme.
+
+use ::assets::{self, InvalidLine, Loaded};
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Red, green, blue and alpha, each between 0 and 1.
+pub type Rgba = [f32; 4];
+
+/// The faces of Noto Sans shipped in `assets/fonts`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FontStyle {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic
+}
+
+impl FontStyle {
+
+    pub fn parse(input: &str) -> Option<FontStyle> {
+        match input.trim().to_lowercase().as_str() {
+            "regular" => Some(FontStyle::Regular),
+            "bold" => Some(FontStyle::Bold),
+            "italic" => Some(FontStyle::Italic),
+            "bold_italic" => Some(FontStyle::BoldItalic),
+            _ => None
+        }
+    }
+
+    /// Path of the font file relative to the assets folder.
+    pub fn path(&self) -> PathBuf {
+        let file_name = match *self {
+            FontStyle::Regular => "NotoSans-Regular.ttf",
+            FontStyle::Bold => "NotoSans-Bold.ttf",
+            FontStyle::Italic => "NotoSans-Italic.ttf",
+            FontStyle::BoldItalic => "NotoSans-BoldItalic.ttf"
+        };
+        Path::new("fonts/NotoSans").join(file_name)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UiTheme {
+    pub code: String,
+    pub name: String,
+    pub font: FontStyle,
+    pub background: Rgba,
+    pub shape: Rgba,
+    pub frame: Rgba,
+    pub frame_width: f64,
+    pub label: Rgba,
+    pub chart_axis: Rgba,
+    pub chart_curve: Rgba,
+    pub chart_marker: Rgba,
+    pub roll_material: Rgba,
+    pub roll_consumed: Rgba,
+    pub roll_ring: Rgba,
+    pub roll_consumed_ring: Rgba,
+    pub roll_core: Rgba
+}
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(io::Error),
+    InvalidLine { line: usize, content: String },
+    UnknownKey { line: usize, key: String },
+    InvalidValue { line: usize, key: String, value: String }
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ThemeError::Io(ref err) => write!(f, "unable to read theme: {}", err),
+            ThemeError::InvalidLine { line, ref content } =>
+                write!(f, "line {}: expected `key = value`, got `{}`", line, content),
+            ThemeError::UnknownKey { line, ref key } =>
+                write!(f, "line {}: unknown theme key `{}`", line, key),
+            ThemeError::InvalidValue { line, ref key, ref value } =>
+                write!(f, "line {}: invalid value `{}` for `{}`", line, value, key)
+        }
+    }
+}
+
+impl From<io::Error> for ThemeError {
+    fn from(err: io::Error) -> ThemeError {
+        ThemeError::Io(err)
+    }
+}
+
+impl From<InvalidLine> for ThemeError {
+    fn from(err: InvalidLine) -> ThemeError {
+        ThemeError::InvalidLine { line: err.line, content: err.content }
+    }
+}
+
+/// Parses a colour written `#RRGGBB` or `#RRGGBBAA`.
+pub fn parse_color(input: &str) -> Option<Rgba> {
+    let hex = input.trim();
+    if !hex.starts_with('#') || !(hex.len() == 7 || hex.len() == 9) || !hex[1..].chars().all(|ch| ch.is_digit(16)) {
+        return None;
+    }
+
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).ok().map(|value| value as f32 / 255.0);
+    let alpha = if hex.len() == 9 { channel(7) } else { Some(1.0) };
+    match (channel(1), channel(3), channel(5), alpha) {
+        (Some(red), Some(green), Some(blue), Some(alpha)) => Some([red, green, blue, alpha]),
+        _ => None
+    }
+}
+
+fn rgb(red: u8, green: u8, blue: u8) -> Rgba {
+    [red as f32 / 255.0, green as f32 / 255.0, blue as f32 / 255.0, 1.0]
+}
+
+impl UiTheme {
+
+    /// The original look of the application, built in so there is always a theme to fall
+    /// back to.
+    pub fn classic() -> UiTheme {
+        UiTheme {
+            code: "classic".to_string(),
+            name: "Classic".to_string(),
+            font: FontStyle::Regular,
+            background: [0.3, 0.4, 0.5, 1.0],
+            shape: rgb(255, 255, 255),
+            frame: rgb(0, 0, 0),
+            frame_width: 1.0,
+            label: rgb(0, 0, 0),
+            chart_axis: rgb(255, 255, 255),
+            chart_curve: rgb(255, 233, 79),
+            chart_marker: rgb(211, 215, 207),
+            roll_material: rgb(233, 185, 110),
+            roll_consumed: rgb(238, 238, 236),
+            roll_ring: rgb(193, 125, 17),
+            roll_consumed_ring: rgb(211, 215, 207),
+            roll_core: rgb(46, 52, 54)
+        }
+    }
+
+    pub fn parse(code: &str, text: &str) -> Result<UiTheme, ThemeError> {
+        let mut theme = UiTheme::classic();
+        theme.code = code.to_string();
+        theme.name = code.to_string();
+
+        for setting in try!(assets::parse_settings(text)) {
+            let (line, key, value) = (setting.line, setting.key, setting.value);
+            let invalid_value = || ThemeError::InvalidValue { line: line, key: key.to_string(), value: value.to_string() };
+
+            match key {
+                "name" => theme.name = value.to_string(),
+                "font" => theme.font = try!(FontStyle::parse(value).ok_or(invalid_value())),
+                "frame_width" => theme.frame_width = match value.parse::<f64>() {
+                    Ok(width) if width >= 0.0 => width,
+                    _ => return Err(invalid_value())
+                },
+                _ => {
+                    let field = match key {
+                        "background" => &mut theme.background,
+                        "shape" => &mut theme.shape,
+                        "frame" => &mut theme.frame,
+                        "label" => &mut theme.label,
+                        "chart.axis" => &mut theme.chart_axis,
+                        "chart.curve" => &mut theme.chart_curve,
+                        "chart.marker" => &mut theme.chart_marker,
+                        "roll.material" => &mut theme.roll_material,
+                        "roll.consumed" => &mut theme.roll_consumed,
+                        "roll.ring" => &mut theme.roll_ring,
+                        "roll.consumed_ring" => &mut theme.roll_consumed_ring,
+                        "roll.core" => &mut theme.roll_core,
+                        _ => return Err(ThemeError::UnknownKey { line: line, key: key.to_string() })
+                    };
+                    *field = try!(parse_color(value).ok_or(invalid_value()));
+                }
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Loads a theme whose code is the file name, e.g. `high_contrast.txt`.
+    pub fn load_file(path: &Path) -> Result<UiTheme, ThemeError> {
+        let (code, text) = try!(assets::read_named(path));
+        UiTheme::parse(&code, &text)
+    }
+
+    /// Loads every `.txt` theme in `dir`, sorted by file name. Themes that fail to load are
+    /// skipped.
+    pub fn load_dir(dir: &Path) -> io::Result<Loaded<UiTheme, ThemeError>> {
+        assets::load_dir(dir, "txt", UiTheme::load_file)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn colors_should_be_parsed_from_hex() {
+        assert_eq!(Some([1.0, 0.0, 0.2, 1.0]), parse_color("#FF0033"));
+        assert_eq!(Some([0.0, 0.0, 0.0, 0.6]), parse_color(" #00000099 "));
+        assert_eq!(None, parse_color("FF0033"));
+        assert_eq!(None, parse_color("#FF00"));
+        assert_eq!(None, parse_color("#GG0033"));
+    }
+
+    #[test]
+    fn missing_keys_should_keep_classic_values() {
+        let theme = UiTheme::parse("dark", "name = Dark\nfont = bold\nbackground = #000000").unwrap();
+        let classic = UiTheme::classic();
+        assert_eq!("dark", theme.code);
+        assert_eq!("Dark", theme.name);
+        assert_eq!(FontStyle::Bold, theme.font);
+        assert_eq!([0.0, 0.0, 0.0, 1.0], theme.background);
+        assert_eq!(classic.label, theme.label);
+        assert_eq!(Path::new("fonts/NotoSans/NotoSans-Bold.ttf"), theme.font.path().as_path());
+    }
+
+    #[test]
+    fn invalid_theme_lines_should_be_reported() {
+        match UiTheme::parse("x", "# comment\nbackgrund = #000000") {
+            Err(ThemeError::UnknownKey { line: 2, .. }) => {},
+            other => panic!("Expected unknown key error, got {:?}", other)
+        }
+        match UiTheme::parse("x", "label = black") {
+            Err(ThemeError::InvalidValue { line: 1, .. }) => {},
+            other => panic!("Expected invalid value error, got {:?}", other)
+        }
+        match UiTheme::parse("x", "frame_width = -1") {
+            Err(ThemeError::InvalidValue { line: 1, .. }) => {},
+            other => panic!("Expected invalid value error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn bundled_themes_should_load() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/themes");
+        let loaded = UiTheme::load_dir(&dir).unwrap();
+        let codes = loaded.items.into_iter().map(|theme| theme.code).collect::<Vec<String>>();
+        assert_eq!(vec!["dark", "high_contrast", "light"], codes);
+        assert!(loaded.skipped.is_empty());
+    }
+}
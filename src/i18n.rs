@@ -0,0 +1,209 @@
+use ::assets::{self, InvalidLine};
+use ::estimator::units::{LengthUnit, Unit};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Messages for one language, read from a catalog where each line reads `key = message`.
+/// Messages may contain `{name}` placeholders, filled in by `Translator::tr_args`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Catalog {
+    pub code: String,
+    messages: HashMap<String, String>
+}
+
+#[derive(Debug)]
+pub enum CatalogError {
+    Io(io::Error),
+    InvalidLine { line: usize, content: String }
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CatalogError::Io(ref err) => write!(f, "unable to read message catalog: {}", err),
+            CatalogError::InvalidLine { line, ref content } =>
+                write!(f, "line {}: expected `key = message`, got `{}`", line, content)
+        }
+    }
+}
+
+impl From<io::Error> for CatalogError {
+    fn from(err: io::Error) -> CatalogError {
+        CatalogError::Io(err)
+    }
+}
+
+impl From<InvalidLine> for CatalogError {
+    fn from(err: InvalidLine) -> CatalogError {
+        CatalogError::InvalidLine { line: err.line, content: err.content }
+    }
+}
+
+const ENGLISH_CATALOG: &'static str = include_str!("../assets/i18n/en.txt");
+
+impl Catalog {
+
+    pub fn parse(code: &str, text: &str) -> Result<Catalog, CatalogError> {
+        let mut messages = HashMap::new();
+        for setting in try!(assets::parse_settings(text)) {
+            messages.insert(setting.key.to_string(), setting.value.to_string());
+        }
+        Ok(Catalog { code: code.to_string(), messages: messages })
+    }
+
+    /// Loads a catalog whose language code is the file name, e.g. `de.txt`.
+    pub fn load_file(path: &Path) -> Result<Catalog, CatalogError> {
+        let (code, text) = try!(assets::read_named(path));
+        Catalog::parse(&code, &text)
+    }
+
+    /// The English catalog, built into the application so there is always a fallback.
+    pub fn english() -> Catalog {
+        Catalog::parse("en", ENGLISH_CATALOG).unwrap()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(|message| message.as_str())
+    }
+
+    /// The language's own name for itself, e.g. `Deutsch`.
+    pub fn name(&self) -> &str {
+        self.get("language.name").unwrap_or(&self.code)
+    }
+}
+
+/// The available catalogs and the selected language. Messages missing from the selected
+/// catalog fall back to English, and then to the key itself.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Translator {
+    catalogs: Vec<Catalog>,
+    pub selected: Option<usize>
+}
+
+impl Translator {
+
+    pub fn new() -> Translator {
+        Translator {
+            catalogs: vec![Catalog::english()],
+            selected: Some(0)
+        }
+    }
+
+    /// Adds a catalog, replacing any catalog already loaded for the same language.
+    pub fn add(&mut self, catalog: Catalog) {
+        match self.catalogs.iter().position(|existing| existing.code == catalog.code) {
+            Some(idx) => self.catalogs[idx] = catalog,
+            None => self.catalogs.push(catalog)
+        }
+    }
+
+    /// Loads every `.txt` catalog in `dir`, returning the files that were skipped because they
+    /// couldn't be loaded.
+    pub fn load_dir(&mut self, dir: &Path) -> io::Result<Vec<(PathBuf, CatalogError)>> {
+        let loaded = try!(assets::load_dir(dir, "txt", Catalog::load_file));
+        for catalog in loaded.items.into_iter() {
+            self.add(catalog);
+        }
+        Ok(loaded.skipped)
+    }
+
+    pub fn get_language_strings(&self) -> Vec<String> {
+        self.catalogs.iter().map(|catalog| catalog.name().to_string()).collect::<Vec<String>>()
+    }
+
+    /// Selects a language by code, returning `false` if no catalog is loaded for it.
+    pub fn select(&mut self, code: &str) -> bool {
+        match self.catalogs.iter().position(|catalog| catalog.code == code) {
+            Some(idx) => {
+                self.selected = Some(idx);
+                true
+            }
+            None => false
+        }
+    }
+
+    pub fn tr(&self, key: &str) -> String {
+        let selected = self.catalogs.get(self.selected.unwrap_or(0)).and_then(|catalog| catalog.get(key));
+        selected.or_else(|| self.catalogs.iter().find(|catalog| catalog.code == "en").and_then(|catalog| catalog.get(key)))
+            .unwrap_or(key)
+            .to_string()
+    }
+
+    /// Translates `key` and replaces each `{name}` placeholder with its value.
+    pub fn tr_args(&self, key: &str, args: &[(&str, &str)]) -> String {
+        args.iter().fold(self.tr(key), |message, &(name, value)| {
+            message.replace(&format!("{{{}}}", name), value)
+        })
+    }
+
+    /// Translates `key`, or returns `fallback` if no catalog has a message for it.
+    pub fn tr_or(&self, key: &str, fallback: &str) -> String {
+        let message = self.tr(key);
+        if message == key { fallback.to_string() } else { message }
+    }
+
+    /// The translated name of a unit, or its own name for units without a translation, such as
+    /// those loaded from a units file.
+    pub fn unit_name(&self, unit: &LengthUnit) -> String {
+        self.tr_or(&format!("unit.{}", unit.abbrev()), unit.full_name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::estimator::units::{LengthUnit, CENTIMETERS};
+
+    fn translator() -> Translator {
+        let mut translator = Translator::new();
+        translator.add(Catalog::parse("de", "language.name = Deutsch\nlabel.od = Außendurchmesser\nunit.cm = Zentimeter").unwrap());
+        translator
+    }
+
+    #[test]
+    fn selected_language_should_be_used_with_english_fallback() {
+        let mut translator = translator();
+        assert!(translator.select("de"));
+        assert_eq!("Außendurchmesser", translator.tr("label.od"));
+        assert_eq!("Inside Diameter", translator.tr("label.id"));
+        assert_eq!("no.such.key", translator.tr("no.such.key"));
+        assert!(!translator.select("xx"));
+    }
+
+    #[test]
+    fn placeholders_should_be_replaced() {
+        let translator = Translator::new();
+        assert_eq!("Total Length: 12.00 yrd", translator.tr_args("label.total_length", &[("value", "12.00 yrd")]));
+    }
+
+    #[test]
+    fn unit_names_should_be_translated_or_fall_back_to_full_name() {
+        let mut translator = translator();
+        translator.select("de");
+        assert_eq!("Zentimeter", translator.unit_name(&CENTIMETERS));
+        assert_eq!("Picas", translator.unit_name(&LengthUnit::new("Picas", "pc", 0.0042333)));
+    }
+
+    #[test]
+    fn invalid_catalog_line_should_be_reported() {
+        match Catalog::parse("de", "# comment\nlabel.od Außendurchmesser") {
+            Err(CatalogError::InvalidLine { line: 2, .. }) => {},
+            other => panic!("Expected invalid line error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn bundled_catalogs_should_define_every_english_key() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/i18n");
+        let english = Catalog::english();
+        for code in ["de", "es"].iter() {
+            let catalog = Catalog::load_file(&dir.join(format!("{}.txt", code))).unwrap();
+            for key in english.messages.keys() {
+                assert!(catalog.get(key).is_some(), "{} is missing {}", code, key);
+            }
+        }
+    }
+}
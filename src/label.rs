@@ -0,0 +1,328 @@
+//! Roll labels for Zebra printers. A label template is a ZPL format with `{name}` placeholders,
+//! which are filled in from the measured roll. The result is sent to the printer's raw port, or
+//! written to a file for printing later.
+//!
+//! Values are escaped for ZPL's `^FH` hexadecimal field data, so a template should put `^FH`
+//! before each `^FD` that contains a placeholder.
+
+use assets::{self, Loaded};
+use estimator::MaterialRoll;
+use estimator::locale::NumberLocale;
+use estimator::units::{Length, LengthUnit, Unit};
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The placeholders a template may use.
+pub const PLACEHOLDERS: [&'static str; 8] = ["material", "roll_id", "thickness", "od", "id", "width", "length", "layers"];
+
+/// The raw printing port used by Zebra and most other label printers.
+pub const DEFAULT_PRINTER_PORT: u16 = 9100;
+
+const PRINTER_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Debug)]
+pub enum LabelError {
+    Io(io::Error),
+    UnknownPlaceholder { line: usize, name: String }
+}
+
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LabelError::Io(ref err) => write!(f, "unable to read label template: {}", err),
+            LabelError::UnknownPlaceholder { line, ref name } =>
+                write!(f, "line {}: unknown placeholder `{{{}}}`", line, name)
+        }
+    }
+}
+
+impl From<io::Error> for LabelError {
+    fn from(err: io::Error) -> LabelError {
+        LabelError::Io(err)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LabelTemplate {
+    pub name: String,
+    pub text: String
+}
+
+impl LabelTemplate {
+
+    /// The 4 x 2 inch label shipped in `assets/labels`, built in so there is always a template
+    /// to print with.
+    pub fn standard() -> LabelTemplate {
+        LabelTemplate {
+            name: "standard".to_string(),
+            text: include_str!("../assets/labels/standard.zpl").to_string()
+        }
+    }
+
+    /// Checks that every placeholder in `text` is one of `PLACEHOLDERS`.
+    pub fn parse(name: &str, text: &str) -> Result<LabelTemplate, LabelError> {
+        for (idx, line) in text.lines().enumerate() {
+            for piece in line.split('{').skip(1) {
+                if let Some(end) = piece.find('}') {
+                    let placeholder = &piece[..end];
+                    if !PLACEHOLDERS.contains(&placeholder) {
+                        return Err(LabelError::UnknownPlaceholder { line: idx + 1, name: placeholder.to_string() });
+                    }
+                }
+            }
+        }
+        Ok(LabelTemplate { name: name.to_string(), text: text.to_string() })
+    }
+
+    /// Loads a template named after its file, e.g. `large.zpl`.
+    pub fn load_file(path: &Path) -> Result<LabelTemplate, LabelError> {
+        let (name, text) = try!(assets::read_named(path));
+        LabelTemplate::parse(&name, &text)
+    }
+
+    /// Loads every `.zpl` template in `dir`, sorted by file name. Templates that fail to load
+    /// are skipped.
+    pub fn load_dir(dir: &Path) -> io::Result<Loaded<LabelTemplate, LabelError>> {
+        assets::load_dir(dir, "zpl", LabelTemplate::load_file)
+    }
+}
+
+/// Escapes a value for a `^FH` field. Underscore starts a hexadecimal escape, and caret and
+/// tilde would otherwise start a new command.
+pub fn escape_field(value: &str) -> String {
+    value.chars().map(|ch| match ch {
+        '_' => "_5F".to_string(),
+        '^' => "_5E".to_string(),
+        '~' => "_7E".to_string(),
+        '\r' | '\n' => " ".to_string(),
+        _ => ch.to_string()
+    }).collect::<String>()
+}
+
+/// A measured roll and the details printed alongside it.
+#[derive(Debug, Clone)]
+pub struct RollLabel {
+    pub material: String,
+    pub roll_id: String,
+    pub roll: MaterialRoll,
+    pub width: Option<Length>,
+    pub length_unit: LengthUnit,
+    pub locale: NumberLocale
+}
+
+impl RollLabel {
+
+    /// The value of each placeholder. Dimensions are shown in the units they were entered in,
+    /// and the width is left blank when it isn't known.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let length = self.roll.get_roll_length().convert_to(self.length_unit.clone());
+        vec![
+            ("material", self.material.clone()),
+            ("roll_id", self.roll_id.clone()),
+            ("thickness", self.locale.format_length(&self.roll.thickness)),
+            ("od", self.locale.format_length(&self.roll.od)),
+            ("id", self.locale.format_length(&self.roll.id)),
+            ("width", self.width.as_ref().map(|width| self.locale.format_length(width)).unwrap_or_default()),
+            ("length", self.locale.format_length(&length)),
+            ("layers", self.roll.get_layer_count().to_string())
+        ]
+    }
+
+    /// Fills in the template's placeholders in a single pass, so a value that looks like a
+    /// placeholder, such as a material named `{od}`, is printed as entered.
+    pub fn render(&self, template: &LabelTemplate) -> String {
+        let fields = self.fields();
+        let mut label = String::with_capacity(template.text.len());
+        let mut rest = &template.text[..];
+        while let Some(start) = rest.find('{') {
+            label.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let field = after.find('}').and_then(|end| {
+                fields.iter().find(|&&(name, _)| name == &after[..end]).map(|&(_, ref value)| (end, value))
+            });
+            match field {
+                Some((end, value)) => {
+                    label.push_str(&escape_field(value));
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    label.push('{');
+                    rest = after;
+                }
+            }
+        }
+        label.push_str(rest);
+        label
+    }
+}
+
+/// Where a label is sent.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PrintTarget {
+    File(PathBuf),
+    /// A printer's raw port, as `host:port`.
+    Tcp(String)
+}
+
+impl PrintTarget {
+
+    /// Reads `tcp://host[:port]` as a printer, using port 9100 when none is given, and
+    /// anything else as a file path.
+    pub fn parse(input: &str) -> Option<PrintTarget> {
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+
+        if input.starts_with("tcp://") {
+            let address = &input["tcp://".len()..];
+            if address.is_empty() {
+                None
+            } else if address.contains(':') {
+                Some(PrintTarget::Tcp(address.to_string()))
+            } else {
+                Some(PrintTarget::Tcp(format!("{}:{}", address, DEFAULT_PRINTER_PORT)))
+            }
+        } else {
+            Some(PrintTarget::File(PathBuf::from(input)))
+        }
+    }
+}
+
+impl fmt::Display for PrintTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PrintTarget::File(ref path) => write!(f, "{}", path.display()),
+            PrintTarget::Tcp(ref address) => write!(f, "tcp://{}", address)
+        }
+    }
+}
+
+/// Sends `zpl` to the target. Connecting to a printer gives up after a few seconds, so an
+/// unreachable printer doesn't hang the interface.
+pub fn print(zpl: &str, target: &PrintTarget) -> io::Result<()> {
+    match *target {
+        PrintTarget::File(ref path) => try!(File::create(path)).write_all(zpl.as_bytes()),
+        PrintTarget::Tcp(ref address) => {
+            let timeout = Duration::from_secs(PRINTER_TIMEOUT_SECS);
+            let mut last_err = io::Error::new(io::ErrorKind::NotFound, format!("no address found for {}", address));
+            for socket_address in try!(address.as_str().to_socket_addrs()) {
+                match TcpStream::connect_timeout(&socket_address, timeout) {
+                    Ok(mut stream) => {
+                        try!(stream.set_write_timeout(Some(timeout)));
+                        try!(stream.write_all(zpl.as_bytes()));
+                        return stream.flush();
+                    }
+                    Err(err) => last_err = err
+                }
+            }
+            Err(last_err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use estimator::locale::{ENGLISH, GERMAN};
+    use estimator::units::{CENTIMETERS, METERS};
+    use std::env;
+    use std::fs;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::process;
+    use std::thread;
+
+    fn roll_label() -> RollLabel {
+        RollLabel {
+            material: "Canvas_10^oz".to_string(),
+            roll_id: "R-1042".to_string(),
+            roll: MaterialRoll {
+                id: Length::new(4.0, CENTIMETERS),
+                od: Length::new(12.0, CENTIMETERS),
+                thickness: Length::new(0.08, CENTIMETERS)
+            },
+            width: None,
+            length_unit: METERS,
+            locale: ENGLISH
+        }
+    }
+
+    #[test]
+    fn render_should_fill_and_escape_placeholders() {
+        let template = LabelTemplate::parse("test", "^XA^FH^FD{material}^FS^FD{od} / {id} / {width}^FS^FD{layers}^FS^XZ").unwrap();
+        assert_eq!("^XA^FH^FDCanvas_5F10_5Eoz^FS^FD12.00 cm / 4.00 cm / ^FS^FD100^FS^XZ", roll_label().render(&template));
+
+        let mut label = roll_label();
+        label.locale = GERMAN;
+        label.width = Some(Length::new(1.5, METERS));
+        let template = LabelTemplate::parse("test", "{width} {length}").unwrap();
+        assert_eq!(format!("1,50 m {}", GERMAN.format_length(&label.roll.get_roll_length().convert_to(METERS))),
+            label.render(&template));
+    }
+
+    #[test]
+    fn values_that_look_like_placeholders_should_be_printed_as_entered() {
+        let mut label = roll_label();
+        label.material = "{od}".to_string();
+        label.roll_id = "{".to_string();
+        let template = LabelTemplate::parse("test", "{material} {roll_id}{id} {od}").unwrap();
+        assert_eq!("{od} {4.00 cm 12.00 cm", label.render(&template));
+    }
+
+    #[test]
+    fn unknown_placeholders_should_be_reported() {
+        match LabelTemplate::parse("test", "^XA\n^FD{material}^FS\n^FD{colour}^FS") {
+            Err(LabelError::UnknownPlaceholder { line: 3, ref name }) if name == "colour" => {},
+            other => panic!("Expected unknown placeholder error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn bundled_templates_should_load() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/labels");
+        let names = LabelTemplate::load_dir(&dir).unwrap().items.into_iter().map(|template| template.name).collect::<Vec<String>>();
+        assert_eq!(vec!["large", "standard"], names);
+        assert!(LabelTemplate::parse("standard", &LabelTemplate::standard().text).is_ok());
+    }
+
+    #[test]
+    fn print_target_should_default_to_raw_port() {
+        assert_eq!(Some(PrintTarget::Tcp("10.0.0.5:9100".to_string())), PrintTarget::parse("tcp://10.0.0.5"));
+        assert_eq!(Some(PrintTarget::Tcp("zebra:6101".to_string())), PrintTarget::parse(" tcp://zebra:6101 "));
+        assert_eq!(Some(PrintTarget::File(PathBuf::from("label.zpl"))), PrintTarget::parse("label.zpl"));
+        assert_eq!(None, PrintTarget::parse("tcp://"));
+        assert_eq!(None, PrintTarget::parse(" "));
+    }
+
+    #[test]
+    fn print_should_write_to_file() {
+        let path = env::temp_dir().join(format!("fabric_roll_label_test_{}.zpl", process::id()));
+        print("^XA^XZ", &PrintTarget::File(path.clone())).unwrap();
+        let mut written = String::new();
+        File::open(&path).unwrap().read_to_string(&mut written).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!("^XA^XZ", written);
+    }
+
+    #[test]
+    fn print_should_send_to_printer_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let printer = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut received = String::new();
+            stream.read_to_string(&mut received).unwrap();
+            received
+        });
+
+        let zpl = roll_label().render(&LabelTemplate::standard());
+        print(&zpl, &PrintTarget::Tcp(address)).unwrap();
+        assert_eq!(zpl, printer.join().unwrap());
+    }
+}
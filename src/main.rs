@@ -3,12 +3,34 @@
 #[macro_use]
 extern crate conrod;
 
+mod assets;
 mod estimator;
 mod gui;
+mod i18n;
+mod label;
+mod pdf;
+mod report;
+mod cli;
 
-
+use std::env;
+use std::io::{self, Write};
+use std::process;
 
 
 fn main() {
-    gui::run();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let registry = match cli::load_registry(&args) {
+        Ok(registry) => registry,
+        Err(message) => {
+            let _ = writeln!(io::stderr(), "{}", message);
+            process::exit(1);
+        }
+    };
+
+    if cli::wants_cli(&args) {
+        process::exit(cli::run(&args, &registry));
+    }
+
+    gui::run(&registry);
 }
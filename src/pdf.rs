@@ -0,0 +1,209 @@
+//! A minimal PDF writer for single page documents made of text, lines and circles. Text is set
+//! in the standard Helvetica fonts that every PDF viewer provides, so no font is embedded and
+//! the output needs no PDF library.
+
+/// Red, green and blue, each between 0 and 1.
+pub type Rgb = [f64; 3];
+
+/// A4 portrait, in points.
+pub const A4_WIDTH: f64 = 595.0;
+pub const A4_HEIGHT: f64 = 842.0;
+
+/// Control point distance for approximating a quarter circle with a cubic Bézier curve.
+const KAPPA: f64 = 0.5523;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Font {
+    Regular,
+    Bold
+}
+
+impl Font {
+
+    fn resource_name(&self) -> &'static str {
+        match *self {
+            Font::Regular => "F1",
+            Font::Bold => "F2"
+        }
+    }
+}
+
+/// Encodes text for a PDF string in WinAnsiEncoding, which matches Latin-1 for the accented
+/// letters and symbols used in the translations. Other characters are replaced with `?`.
+pub fn encode_text(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for ch in text.chars() {
+        match ch {
+            '(' | ')' | '\\' => {
+                bytes.push(b'\\');
+                bytes.push(ch as u8);
+            }
+            '€' => bytes.push(0x80),
+            _ if (ch >= ' ' && ch <= '~') || (ch >= '\u{a0}' && ch <= '\u{ff}') => bytes.push(ch as u32 as u8),
+            _ => bytes.push(b'?')
+        }
+    }
+    bytes
+}
+
+/// Rough width of `text` in Helvetica, for centering. Helvetica averages about half an em per
+/// character.
+pub fn text_width(text: &str, size: f64) -> f64 {
+    text.chars().count() as f64 * size * 0.5
+}
+
+/// One page, with its origin at the bottom left and sizes in points.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PdfPage {
+    pub width: f64,
+    pub height: f64,
+    content: Vec<u8>
+}
+
+impl PdfPage {
+
+    pub fn new(width: f64, height: f64) -> PdfPage {
+        PdfPage { width: width, height: height, content: Vec::new() }
+    }
+
+    fn push(&mut self, operators: &str) {
+        self.content.extend_from_slice(operators.as_bytes());
+        self.content.push(b'\n');
+    }
+
+    pub fn text(&mut self, x: f64, y: f64, size: f64, font: Font, color: Rgb, text: &str) {
+        self.push(&format!("BT /{} {:.1} Tf {:.3} {:.3} {:.3} rg {:.2} {:.2} Td", font.resource_name(), size,
+            color[0], color[1], color[2], x, y));
+        self.content.push(b'(');
+        self.content.extend(encode_text(text));
+        self.content.extend_from_slice(b") Tj ET\n");
+    }
+
+    /// Strokes straight segments through `points`.
+    pub fn polyline(&mut self, points: &[[f64; 2]], line_width: f64, color: Rgb) {
+        if points.len() < 2 {
+            return;
+        }
+        let mut path = format!("{:.2} w {:.3} {:.3} {:.3} RG {:.2} {:.2} m", line_width, color[0], color[1], color[2],
+            points[0][0], points[0][1]);
+        for point in points[1..].iter() {
+            path.push_str(&format!(" {:.2} {:.2} l", point[0], point[1]));
+        }
+        path.push_str(" S");
+        self.push(&path);
+    }
+
+    pub fn line(&mut self, from: [f64; 2], to: [f64; 2], line_width: f64, color: Rgb) {
+        self.polyline(&[from, to], line_width, color);
+    }
+
+    pub fn fill_circle(&mut self, center: [f64; 2], radius: f64, color: Rgb) {
+        let path = circle_path(center, radius);
+        self.push(&format!("{:.3} {:.3} {:.3} rg {} f", color[0], color[1], color[2], path));
+    }
+
+    pub fn stroke_circle(&mut self, center: [f64; 2], radius: f64, line_width: f64, color: Rgb) {
+        let path = circle_path(center, radius);
+        self.push(&format!("{:.2} w {:.3} {:.3} {:.3} RG {} S", line_width, color[0], color[1], color[2], path));
+    }
+
+    /// The complete document, with the page as its only page.
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut stream = format!("<< /Length {} >>\nstream\n", self.content.len()).into_bytes();
+        stream.extend_from_slice(&self.content);
+        stream.extend_from_slice(b"endstream");
+
+        let objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            format!("<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.0} {:.0}] \
+                /Resources << /Font << /F1 5 0 R /F2 6 0 R >> >> /Contents 4 0 R >>", self.width, self.height).into_bytes(),
+            stream,
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec()
+        ];
+
+        // A comment with high bytes marks the file as binary for tools that guess
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::new();
+        for (idx, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n", idx + 1).into_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = pdf.len();
+        pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+        for offset in offsets.iter() {
+            pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+        }
+        pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref_offset)
+            .into_bytes());
+        pdf
+    }
+}
+
+/// A closed circle made of four Bézier curves, starting from its rightmost point.
+fn circle_path(center: [f64; 2], radius: f64) -> String {
+    let (x, y, r, k) = (center[0], center[1], radius, radius * KAPPA);
+    format!("{:.2} {:.2} m \
+        {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c \
+        {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c \
+        {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c \
+        {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c h",
+        x + r, y,
+        x + r, y + k, x + k, y + r, x, y + r,
+        x - k, y + r, x - r, y + k, x - r, y,
+        x - r, y - k, x - k, y - r, x, y - r,
+        x + k, y - r, x + r, y - k, x + r, y)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+
+    #[test]
+    fn text_should_be_escaped_and_encoded_as_win_ansi() {
+        assert_eq!(b"Length \\(m\\): 1\\\\2".to_vec(), encode_text("Length (m): 1\\2"));
+        assert_eq!(vec![b'M', 0xfc, b'l', b'l', b' ', 0xb2, b' ', 0x80, b' ', b'?'], encode_text("Müll ² € ✓"));
+    }
+
+    #[test]
+    fn cross_reference_table_should_point_at_each_object() {
+        let mut page = PdfPage::new(A4_WIDTH, A4_HEIGHT);
+        page.text(50.0, 800.0, 12.0, Font::Bold, [0.0, 0.0, 0.0], "Größe");
+        page.fill_circle([100.0, 100.0], 20.0, [1.0, 0.0, 0.0]);
+        let pdf = page.to_pdf();
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        assert!(contains(&pdf, b"(Gr\xf6\xdfe) Tj"));
+
+        // Everything from the cross-reference table on is ASCII
+        let trailer = String::from_utf8_lossy(&pdf[pdf.len() - 30..]).into_owned();
+        let xref_offset = trailer.lines().rev().nth(1).unwrap().parse::<usize>().unwrap();
+        let xref = String::from_utf8(pdf[xref_offset..].to_vec()).unwrap();
+        assert!(xref.starts_with("xref"));
+        let entries = xref.lines().skip(3).take(6).collect::<Vec<&str>>();
+        for (idx, entry) in entries.iter().enumerate() {
+            let offset = entry[..10].parse::<usize>().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", idx + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn stream_length_should_match_content() {
+        let mut page = PdfPage::new(100.0, 100.0);
+        page.line([0.0, 0.0], [100.0, 100.0], 1.0, [0.0, 0.0, 0.0]);
+        let pdf = page.to_pdf();
+        let text = String::from_utf8_lossy(&pdf).into_owned();
+        let start = text.find("stream\n").unwrap() + "stream\n".len();
+        let end = text.find("endstream").unwrap();
+        assert!(text.contains(&format!("/Length {}", end - start)));
+        assert!(text[start..end].contains("0.00 0.00 m 100.00 100.00 l S"));
+    }
+}
@@ -0,0 +1,297 @@
+//! One page PDF report of a roll, for quality records. The report lists the inputs and results,
+//! then draws the roll's cross-section and its length-vs-diameter chart as vector graphics.
+
+use estimator::MaterialRoll;
+use estimator::locale::NumberLocale;
+use estimator::quantities::{Mass, KILOGRAMS};
+use estimator::units::{LengthUnit, Unit, CENTIMETERS};
+use i18n::Translator;
+use pdf::{self, Font, PdfPage, Rgb, A4_HEIGHT, A4_WIDTH};
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Samples taken along the length curve for the length-vs-diameter chart, in the report and on
+/// screen.
+pub const CHART_SAMPLES: usize = 50;
+
+/// Maximum number of layer boundaries drawn in a cross-section, in the report and on screen.
+/// Rolls with more wraps than this have their rings sampled evenly so the drawing stays readable.
+pub const MAX_RINGS: usize = 24;
+
+const CHART_TICKS: usize = 5;
+const MARGIN: f64 = 50.0;
+
+const BLACK: Rgb = [0.0, 0.0, 0.0];
+const GRID: Rgb = [0.85, 0.85, 0.85];
+const CURVE: Rgb = [0.125, 0.29, 0.529];
+const MATERIAL: Rgb = [0.914, 0.725, 0.431];
+const RING: Rgb = [0.757, 0.49, 0.067];
+const CORE: Rgb = [0.18, 0.204, 0.212];
+
+/// The layer boundaries to draw as rings for a roll of `layer_count` layers, counted from the
+/// core. The core and the outside of the roll are drawn separately and not included.
+pub fn ring_layers(layer_count: u32) -> Vec<u32> {
+    let ring_step = ((layer_count as usize + MAX_RINGS - 1) / MAX_RINGS).max(1) as u32;
+    (1..layer_count).filter(|layer| layer % ring_step == 0).collect::<Vec<u32>>()
+}
+
+#[derive(Debug, Clone)]
+pub struct RollReport {
+    pub title: String,
+    /// Inputs and results, one per line, already formatted for display.
+    pub lines: Vec<String>,
+    pub roll: MaterialRoll,
+    pub diameter_unit: LengthUnit,
+    pub length_unit: LengthUnit,
+    pub diameter_label: String,
+    pub length_label: String,
+    pub locale: NumberLocale
+}
+
+/// The inputs listed in a summary, already formatted. The GUI repeats what was typed, while the
+/// command line formats the values it parsed. The width and basis weight are optional.
+#[derive(Debug, Clone)]
+pub struct SummaryInputs {
+    pub thickness: String,
+    pub od: String,
+    pub id: String,
+    pub width: Option<String>,
+    pub basis_weight: Option<String>
+}
+
+/// One line per input followed by the results, labelled in the translator's language, as copied
+/// from the GUI and listed at the top of reports. `length` is the roll length as it should be
+/// shown, and the weight is left out when it couldn't be calculated.
+pub fn summary_lines(roll: &MaterialRoll, inputs: &SummaryInputs, length: &str, weight: Option<Mass>,
+        locale: &NumberLocale, translator: &Translator) -> Vec<String> {
+    let line = |label_key: &str, value: &str| {
+        translator.tr_args("summary.line", &[("label", &translator.tr(label_key)), ("value", value)])
+    };
+
+    let mut lines = vec![
+        line("label.thickness", &inputs.thickness),
+        line("label.od", &inputs.od),
+        line("label.id", &inputs.id)
+    ];
+    if let Some(ref width) = inputs.width {
+        lines.push(line("label.width", width));
+    }
+    if let Some(ref basis_weight) = inputs.basis_weight {
+        lines.push(line("label.basis_weight", basis_weight));
+    }
+
+    lines.push(translator.tr_args("label.total_length", &[("value", length)]));
+    lines.push(translator.tr_args("label.layers", &[("count", &roll.get_layer_count().to_string())]));
+    if let Some(weight) = weight {
+        let kilograms = format!("{} {}", locale.format_number(weight.value(&KILOGRAMS), 2), KILOGRAMS.abbrev());
+        lines.push(translator.tr_args("label.weight", &[("value", &kilograms)]));
+    }
+    lines
+}
+
+impl RollReport {
+
+    /// A report titled and with chart labels in the translator's language.
+    pub fn new(roll: MaterialRoll, lines: Vec<String>, diameter_unit: LengthUnit, length_unit: LengthUnit,
+            locale: NumberLocale, translator: &Translator) -> RollReport {
+        RollReport {
+            title: translator.tr("report.title"),
+            lines: lines,
+            roll: roll,
+            diameter_label: translator.tr_args("chart.diameter", &[("unit", diameter_unit.abbrev())]),
+            length_label: translator.tr_args("chart.length", &[("unit", length_unit.abbrev())]),
+            diameter_unit: diameter_unit,
+            length_unit: length_unit,
+            locale: locale
+        }
+    }
+
+    /// The length curve as `[diameter, length]` in the report's units, mapped onto the rectangle
+    /// `[left, bottom, width, height]` with the smallest diameter and length at its bottom left.
+    pub fn chart_points(&self, rect: [f64; 4]) -> Vec<[f64; 2]> {
+        let curve = self.roll.get_length_curve(CHART_SAMPLES).iter().map(|&(ref diameter, ref length)| {
+            [diameter.value(&self.diameter_unit), length.value(&self.length_unit)]
+        }).collect::<Vec<[f64; 2]>>();
+        let (min_x, max_x) = range(curve.iter().map(|point| point[0]));
+        let (min_y, max_y) = range(curve.iter().map(|point| point[1]));
+
+        curve.iter().map(|point| {
+            [rect[0] + scale(point[0], min_x, max_x) * rect[2], rect[1] + scale(point[1], min_y, max_y) * rect[3]]
+        }).collect::<Vec<[f64; 2]>>()
+    }
+
+    pub fn to_page(&self) -> PdfPage {
+        let mut page = PdfPage::new(A4_WIDTH, A4_HEIGHT);
+        page.text(MARGIN, A4_HEIGHT - 70.0, 22.0, Font::Bold, BLACK, &self.title);
+        page.line([MARGIN, A4_HEIGHT - 82.0], [A4_WIDTH - MARGIN, A4_HEIGHT - 82.0], 1.0, BLACK);
+
+        for (idx, line) in self.lines.iter().enumerate() {
+            page.text(MARGIN, A4_HEIGHT - 115.0 - 18.0 * idx as f64, 11.0, Font::Regular, BLACK, line);
+        }
+
+        self.draw_cross_section(&mut page, [A4_WIDTH - MARGIN - 95.0, A4_HEIGHT - 200.0], 95.0);
+        self.draw_chart(&mut page, [MARGIN + 40.0, 120.0, A4_WIDTH - 2.0 * MARGIN - 60.0, 330.0]);
+        page
+    }
+
+    pub fn to_pdf(&self) -> Vec<u8> {
+        self.to_page().to_pdf()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        try!(File::create(path)).write_all(&self.to_pdf())
+    }
+
+    /// Draws the roll with its outside diameter filling `radius`, with rings at evenly sampled
+    /// layer boundaries.
+    fn draw_cross_section(&self, page: &mut PdfPage, center: [f64; 2], radius: f64) {
+        let core_radius = radius * self.roll.id.value(&CENTIMETERS) / self.roll.od.value(&CENTIMETERS);
+        let layer_count = self.roll.get_layer_count();
+        let layer_width = (radius - core_radius) / layer_count.max(1) as f64;
+
+        page.fill_circle(center, radius, MATERIAL);
+        for layer in ring_layers(layer_count) {
+            page.stroke_circle(center, core_radius + layer as f64 * layer_width, 0.5, RING);
+        }
+        page.stroke_circle(center, radius, 1.0, RING);
+        page.fill_circle(center, core_radius, CORE);
+    }
+
+    /// Draws the chart with its axes along the left and bottom of `rect`, with evenly spaced
+    /// grid lines labelled in the report's locale.
+    fn draw_chart(&self, page: &mut PdfPage, rect: [f64; 4]) {
+        let (left, bottom, width, height) = (rect[0], rect[1], rect[2], rect[3]);
+        let min_diameter = self.roll.id.value(&self.diameter_unit);
+        let max_diameter = self.roll.od.value(&self.diameter_unit);
+        let max_length = self.roll.get_roll_length().value(&self.length_unit);
+
+        for tick in 0..CHART_TICKS {
+            let fraction = tick as f64 / (CHART_TICKS - 1) as f64;
+            let x = left + fraction * width;
+            let y = bottom + fraction * height;
+            if tick > 0 {
+                page.line([x, bottom], [x, bottom + height], 0.5, GRID);
+                page.line([left, y], [left + width, y], 0.5, GRID);
+            }
+
+            let diameter = self.locale.format_number(min_diameter + fraction * (max_diameter - min_diameter), 2);
+            page.text(x - pdf::text_width(&diameter, 9.0) / 2.0, bottom - 14.0, 9.0, Font::Regular, BLACK, &diameter);
+            let length = self.locale.format_number(fraction * max_length, 1);
+            page.text(left - pdf::text_width(&length, 9.0) - 6.0, y - 3.0, 9.0, Font::Regular, BLACK, &length);
+        }
+
+        page.line([left, bottom], [left + width, bottom], 1.0, BLACK);
+        page.line([left, bottom], [left, bottom + height], 1.0, BLACK);
+        page.polyline(&self.chart_points(rect), 1.5, CURVE);
+
+        let x_label_width = pdf::text_width(&self.diameter_label, 10.0);
+        page.text(left + (width - x_label_width) / 2.0, bottom - 32.0, 10.0, Font::Bold, BLACK, &self.diameter_label);
+        page.text(left - 40.0, bottom + height + 14.0, 10.0, Font::Bold, BLACK, &self.length_label);
+    }
+}
+
+fn range<I>(values: I) -> (f64, f64) where I: Iterator<Item=f64> {
+    values.fold((::std::f64::MAX, ::std::f64::MIN), |(min, max), val| {
+        (min.min(val), max.max(val))
+    })
+}
+
+fn scale(value: f64, min: f64, max: f64) -> f64 {
+    if max > min { (value - min) / (max - min) } else { 0.0 }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use estimator::locale::{ENGLISH, GERMAN};
+    use estimator::quantities::KILOGRAMS;
+    use estimator::units::{Length, INCHES, YARDS};
+
+    fn report() -> RollReport {
+        RollReport {
+            title: "Roll Report".to_string(),
+            lines: vec!["Material Thickness: 0.08 in".to_string(), "Weight: 12,50 kg".to_string()],
+            roll: MaterialRoll {
+                id: Length::new(4.0, INCHES),
+                od: Length::new(12.0, INCHES),
+                thickness: Length::new(0.08, INCHES)
+            },
+            diameter_unit: INCHES,
+            length_unit: YARDS,
+            diameter_label: "Diameter (in)".to_string(),
+            length_label: "Length (yrd)".to_string(),
+            locale: GERMAN
+        }
+    }
+
+    fn inputs() -> SummaryInputs {
+        SummaryInputs {
+            thickness: "0.08 in".to_string(),
+            od: "12 in".to_string(),
+            id: "4 in".to_string(),
+            width: None,
+            basis_weight: Some("80".to_string())
+        }
+    }
+
+    #[test]
+    fn summary_should_list_inputs_then_results() {
+        let roll = report().roll;
+        let lines = summary_lines(&roll, &inputs(), "12.34 yrd", Some(Mass::new(12.5, KILOGRAMS)), &GERMAN, &Translator::new());
+        assert_eq!(vec![
+            "Material Thickness: 0.08 in",
+            "Outside Diameter: 12 in",
+            "Inside Diameter: 4 in",
+            "Basis Weight (g/m²): 80",
+            "Total Length: 12.34 yrd",
+            "Layers: 100",
+            "Weight: 12,50 kg"
+        ], lines);
+        assert_eq!(6, summary_lines(&roll, &inputs(), "", None, &ENGLISH, &Translator::new()).len());
+    }
+
+    #[test]
+    fn new_report_should_label_chart_in_translator_language() {
+        let report = RollReport::new(report().roll, Vec::new(), INCHES, YARDS, GERMAN, &Translator::new());
+        assert_eq!("Roll Report", report.title);
+        assert_eq!("Diameter (in)", report.diameter_label);
+        assert_eq!("Length (yrd)", report.length_label);
+    }
+
+    fn contains(haystack: &[u8], needle: &str) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle.as_bytes())
+    }
+
+    #[test]
+    fn rings_should_be_sampled_evenly_beyond_max_rings() {
+        assert_eq!(vec![1, 2, 3], ring_layers(4));
+        assert!(ring_layers(0).is_empty());
+        let rings = ring_layers(100);
+        assert!(rings.len() <= MAX_RINGS);
+        assert_eq!(vec![5, 10, 15], rings[..3].to_vec());
+    }
+
+    #[test]
+    fn chart_should_span_rect_from_core_to_outside_diameter() {
+        let points = report().chart_points([100.0, 50.0, 400.0, 300.0]);
+        assert_eq!(CHART_SAMPLES, points.len());
+        let first = points[0];
+        let last = points[points.len() - 1];
+        assert!((first[0] - 100.0).abs() < 0.0001 && (first[1] - 50.0).abs() < 0.0001);
+        assert!((last[0] - 500.0).abs() < 0.0001 && (last[1] - 350.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn report_should_contain_text_and_drawings() {
+        let pdf = report().to_pdf();
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(contains(&pdf, "(Roll Report) Tj"));
+        assert!(contains(&pdf, "(Weight: 12,50 kg) Tj"));
+        assert!(contains(&pdf, "(Diameter \\(in\\)) Tj"));
+        // Axis labels are in the report's locale
+        assert!(contains(&pdf, "(12,00) Tj"));
+        assert!(contains(&pdf, " c h f"));
+    }
+}